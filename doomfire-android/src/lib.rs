@@ -0,0 +1,126 @@
+//! Renders the doomfire full screen on Android, paced to use as little battery as possible so it can run as a wallpaper.
+//!
+//! The fire is simulated at a fraction of the screen resolution and scaled up when copied to the window,
+//! frames are capped at `FPS` and nothing is simulated while the app is paused or has no window.
+//!
+//! `Wallpaper` only knows about rgba buffers, so the same code can draw into the surface of a
+//! `WallpaperService` (through `ANativeWindow_fromSurface`) instead of the `NativeActivity` window used here.
+use doomfire::Doomfire;
+use std::time::Duration;
+
+/// Frames per second, the fire looks fine well below the refresh rate of the screen.
+pub const FPS: u64 = 20;
+/// Every pixel of the fire covers `SCALE` x `SCALE` pixels of the screen.
+pub const SCALE: usize = 4;
+
+/// The time between two frames.
+pub const FRAME_TIME: Duration = Duration::from_millis(1000 / FPS);
+
+/// A fire sized for a screen, along with its rendered frame.
+pub struct Wallpaper {
+    fire: Doomfire,
+    frame: Vec<u8>,
+}
+
+impl Wallpaper {
+    /// Returns a lit fire for a `width` x `height` screen.
+    pub fn new(width: usize, height: usize) -> Wallpaper {
+        let mut fire = Doomfire::new((width / SCALE).max(1), (height / SCALE).max(1));
+        fire.ignite();
+        Wallpaper {
+            frame: vec![0; fire.width() * fire.height() * 4],
+            fire,
+        }
+    }
+
+    /// Restarts the fire when the screen size changed, e.g. after rotating the device.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if (width / SCALE).max(1) != self.fire.width()
+            || (height / SCALE).max(1) != self.fire.height()
+        {
+            *self = Wallpaper::new(width, height);
+        }
+    }
+
+    /// Advances the fire a single step and renders it.
+    pub fn step(&mut self) {
+        self.fire.update();
+        self.fire.draw(&mut self.frame);
+    }
+
+    /// Copies the rendered frame, scaled up, into a `width` x `height` rgba buffer with `stride` pixels per row.
+    pub fn blit(&self, buffer: &mut [u8], width: usize, height: usize, stride: usize) {
+        let fire_width = self.fire.width();
+        for (y, row) in buffer.chunks_mut(stride * 4).take(height).enumerate() {
+            let src = &self.frame[(y / SCALE).min(self.fire.height() - 1) * fire_width * 4..];
+            for (x, pixel) in row[..width * 4].chunks_exact_mut(4).enumerate() {
+                let i = (x / SCALE).min(fire_width - 1) * 4;
+                pixel.copy_from_slice(&src[i..i + 4]);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "android")]
+#[ndk_glue::main]
+fn main() {
+    use ndk::hardware_buffer_format::HardwareBufferFormat;
+    use ndk_glue::Event;
+    use std::{ptr, slice, thread, time::Instant};
+
+    let mut wallpaper: Option<Wallpaper> = None;
+    let mut paused = false;
+
+    loop {
+        while let Some(event) = ndk_glue::poll_events() {
+            match event {
+                Event::Pause => paused = true,
+                Event::Resume => paused = false,
+                // Drop the fire with the window, there is nothing to show it on until a new window is created.
+                Event::WindowDestroyed => wallpaper = None,
+                Event::Destroy => return,
+                _ => {}
+            }
+        }
+
+        let window = match ndk_glue::native_window() {
+            Some(window) if !paused => window,
+            _ => {
+                // Sleep instead of spinning while there is nothing to draw.
+                thread::sleep(FRAME_TIME);
+                continue;
+            }
+        };
+
+        let start = Instant::now();
+        let (width, height) = (window.width() as usize, window.height() as usize);
+        let fire = wallpaper.get_or_insert_with(|| {
+            window
+                .set_buffers_geometry(0, 0, Some(HardwareBufferFormat::R8G8B8A8_UNORM))
+                .unwrap();
+            Wallpaper::new(width, height)
+        });
+        fire.resize(width, height);
+        fire.step();
+
+        unsafe {
+            let mut buffer = std::mem::zeroed::<ndk_sys::ANativeWindow_Buffer>();
+            let window = window.ptr().as_ptr();
+            if ndk_sys::ANativeWindow_lock(window, &mut buffer, ptr::null_mut()) == 0 {
+                let (width, height, stride) = (
+                    buffer.width as usize,
+                    buffer.height as usize,
+                    buffer.stride as usize,
+                );
+                let pixels = slice::from_raw_parts_mut(buffer.bits as *mut u8, stride * height * 4);
+                fire.blit(pixels, width, height, stride);
+                ndk_sys::ANativeWindow_unlockAndPost(window);
+            }
+        }
+        drop(window);
+
+        if let Some(rest) = FRAME_TIME.checked_sub(start.elapsed()) {
+            thread::sleep(rest);
+        }
+    }
+}
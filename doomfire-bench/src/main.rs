@@ -0,0 +1,154 @@
+//! Runs the doomfire headless at several resolutions and reports how fast every code path is.
+//!
+//! `cargo run --release -p doomfire-bench -- --sizes 320x200,1920x1080 --seconds 2`
+use clap::{App, Arg};
+use doomfire::Doomfire;
+use std::time::{Duration, Instant};
+
+/// A code path to measure, run once per iteration on a lit fire and its frame.
+struct Path {
+    name: &'static str,
+    run: fn(&mut Doomfire, &mut [u8]),
+}
+
+const PATHS: &[Path] = &[
+    Path {
+        name: "update",
+        run: |fire, _| fire.update(),
+    },
+    Path {
+        name: "draw",
+        run: |fire, frame| fire.draw(frame),
+    },
+    Path {
+        name: "update_parallel",
+        run: |fire, _| {
+            fire.set_parallel(true);
+            fire.update();
+        },
+    },
+    Path {
+        name: "update_simd",
+        run: |fire, _| {
+            fire.set_simd(true);
+            fire.update();
+        },
+    },
+    Path {
+        name: "update+draw",
+        run: |fire, frame| {
+            fire.update();
+            fire.draw(frame);
+        },
+    },
+    Path {
+        name: "update_and_draw",
+        run: |fire, frame| fire.update_and_draw(frame),
+    },
+];
+
+fn parse_size(size: &str) -> Result<(usize, usize), String> {
+    let mut parts = size.split('x').map(|p| p.trim().parse::<usize>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(width)), Some(Ok(height)), None) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(format!("invalid size '{}', expected WIDTHxHEIGHT", size)),
+    }
+}
+
+/// Runs `path` for at least `duration` and returns the number of iterations per second.
+fn measure(path: &Path, fire: &mut Doomfire, frame: &mut [u8], duration: Duration) -> f64 {
+    // Warm up the caches and let the fire grow to its usual height first.
+    for _ in 0..fire.height() {
+        fire.update();
+    }
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    while start.elapsed() < duration {
+        (path.run)(fire, frame);
+        iterations += 1;
+    }
+    iterations as f64 / start.elapsed().as_secs_f64()
+}
+
+fn main() {
+    let names: Vec<_> = PATHS.iter().map(|p| p.name).collect();
+    let matches = App::new("doomfire-bench")
+        .about("Measures the doomfire at several resolutions")
+        .arg(
+            Arg::with_name("sizes")
+                .long("sizes")
+                .takes_value(true)
+                .default_value("320x200,600x400,1280x720,1920x1080")
+                .help("Comma separated resolutions to run at"),
+        )
+        .arg(
+            Arg::with_name("seconds")
+                .long("seconds")
+                .takes_value(true)
+                .default_value("1")
+                .help("How long every path runs at every resolution"),
+        )
+        .arg(
+            Arg::with_name("paths")
+                .long("paths")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&names)
+                .help("The code paths to compare, all of them by default"),
+        )
+        .get_matches();
+
+    let sizes: Vec<_> = match matches
+        .value_of("sizes")
+        .unwrap()
+        .split(',')
+        .map(parse_size)
+        .collect()
+    {
+        Ok(sizes) => sizes,
+        Err(e) => clap::Error::with_description(&e, clap::ErrorKind::InvalidValue).exit(),
+    };
+    let seconds: f64 = matches
+        .value_of("seconds")
+        .unwrap()
+        .parse()
+        .unwrap_or_else(|_| {
+            clap::Error::with_description(
+                "invalid number of seconds",
+                clap::ErrorKind::InvalidValue,
+            )
+            .exit()
+        });
+    let duration = Duration::from_secs_f64(seconds);
+    let paths: Vec<_> = match matches.values_of("paths") {
+        Some(selected) => {
+            let selected: Vec<_> = selected.collect();
+            PATHS
+                .iter()
+                .filter(|p| selected.contains(&p.name))
+                .collect()
+        }
+        None => PATHS.iter().collect(),
+    };
+
+    println!(
+        "{:<12} {:<16} {:>12} {:>12}",
+        "size", "path", "per second", "Mpixels/s"
+    );
+    for &(width, height) in &sizes {
+        let mut frame = vec![0; width * height * 4];
+        for path in &paths {
+            let mut fire = Doomfire::new(width, height);
+            fire.ignite();
+            let per_second = measure(path, &mut fire, &mut frame, duration);
+            println!(
+                "{:<12} {:<16} {:>12.1} {:>12.1}",
+                format!("{}x{}", width, height),
+                path.name,
+                per_second,
+                per_second * (width * height) as f64 / 1e6
+            );
+        }
+    }
+}
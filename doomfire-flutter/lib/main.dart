@@ -0,0 +1,79 @@
+// A Flutter app burning the doomfire full screen, tap to extinguish/ignite it and drag sideways to blow it around.
+import 'dart:async';
+import 'dart:ui' as ui;
+
+import 'package:flutter/material.dart';
+import 'package:doomfire_flutter/src/rust/api/fire.dart';
+import 'package:doomfire_flutter/src/rust/frb_generated.dart';
+
+const width = 320;
+const height = 168;
+// The original fire runs at 30 updates per second.
+const frameTime = Duration(milliseconds: 1000 ~/ 30);
+
+Future<void> main() async {
+  await RustLib.init();
+  runApp(const MaterialApp(home: FirePage()));
+}
+
+class FirePage extends StatefulWidget {
+  const FirePage({super.key});
+
+  @override
+  State<FirePage> createState() => _FirePageState();
+}
+
+class _FirePageState extends State<FirePage> {
+  final fire = Fire(width: width, height: height);
+  late final Timer timer;
+  ui.Image? image;
+  // Decoding is asynchronous, skip frames rather than piling them up on slow devices.
+  bool decoding = false;
+
+  @override
+  void initState() {
+    super.initState();
+    timer = Timer.periodic(frameTime, (_) => step());
+  }
+
+  void step() {
+    if (decoding) return;
+    decoding = true;
+    final pixels = fire.updateAndDraw();
+    ui.decodeImageFromPixels(pixels, width, height, ui.PixelFormat.rgba8888, (decoded) {
+      decoding = false;
+      if (!mounted) {
+        decoded.dispose();
+        return;
+      }
+      setState(() {
+        image?.dispose();
+        image = decoded;
+      });
+    });
+  }
+
+  @override
+  void dispose() {
+    timer.cancel();
+    image?.dispose();
+    fire.dispose();
+    super.dispose();
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    return GestureDetector(
+      onTap: fire.toggle,
+      onHorizontalDragUpdate: (details) =>
+          fire.setWind(wind: (details.delta.dx / 20).clamp(-1.0, 1.0)),
+      onHorizontalDragEnd: (_) => fire.setWind(wind: 0),
+      child: Container(
+        color: Colors.black,
+        alignment: Alignment.bottomCenter,
+        // Nearest neighbour scaling keeps the chunky pixels of the original.
+        child: RawImage(image: image, width: double.infinity, fit: BoxFit.fitWidth, filterQuality: FilterQuality.none),
+      ),
+    );
+  }
+}
@@ -0,0 +1,60 @@
+//! The API exposed to Dart, flutter_rust_bridge generates the `Fire` class in `lib/src/rust` from it.
+use doomfire::Doomfire;
+use flutter_rust_bridge::frb;
+
+/// A fire and the rgba frame it draws to, kept on the Rust side so every frame only copies the pixels once.
+#[frb(opaque)]
+pub struct Fire {
+    doomfire: Doomfire,
+    frame: Vec<u8>,
+}
+
+impl Fire {
+    /// Returns a lit fire of `width` x `height` pixels.
+    #[frb(sync)]
+    pub fn new(width: u32, height: u32) -> Fire {
+        let mut doomfire = Doomfire::new(width as usize, height as usize);
+        doomfire.ignite();
+        Fire {
+            frame: vec![0; doomfire.width() * doomfire.height() * 4],
+            doomfire,
+        }
+    }
+
+    #[frb(sync, getter)]
+    pub fn width(&self) -> u32 {
+        self.doomfire.width() as u32
+    }
+
+    #[frb(sync, getter)]
+    pub fn height(&self) -> u32 {
+        self.doomfire.height() as u32
+    }
+
+    #[frb(sync, getter)]
+    pub fn is_lit(&self) -> bool {
+        self.doomfire.is_lit
+    }
+
+    /// Ignites an extinguished fire and extinguishes a lit one.
+    #[frb(sync)]
+    pub fn toggle(&mut self) {
+        if self.doomfire.is_lit {
+            self.doomfire.extinguish();
+        } else {
+            self.doomfire.ignite();
+        }
+    }
+
+    #[frb(sync)]
+    pub fn set_wind(&mut self, wind: f32) {
+        self.doomfire.set_wind(wind);
+    }
+
+    /// Updates the fire a step and returns the rgba pixels of the new frame, a `Uint8List` in Dart.
+    #[frb(sync)]
+    pub fn update_and_draw(&mut self) -> Vec<u8> {
+        self.doomfire.update_and_draw(&mut self.frame);
+        self.frame.clone()
+    }
+}
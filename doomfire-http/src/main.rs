@@ -0,0 +1,101 @@
+//! A headless doomfire controlled over HTTP with `tiny_http`, e.g. to drive a fire rendered on another machine.
+//!
+//! `cargo run --release -p doomfire-http -- 0.0.0.0:8000` listens on all interfaces, the default is `127.0.0.1:8000`.
+//!
+//! | Request              | Body        | Effect                                   |
+//! |----------------------|-------------|------------------------------------------|
+//! | `POST /ignite`       |             | ignites the fire                         |
+//! | `POST /extinguish`   |             | extinguishes the fire                    |
+//! | `POST /wind`         | -1.0 to 1.0 | blows the flames right (> 0) or left     |
+//! | `POST /intensity`    | 0.0 to 1.0  | sets how hot the bottom row burns        |
+//! | `GET /snapshot.png`  |             | returns the current frame as a PNG       |
+//!
+//! `curl -d 0.5 localhost:8000/wind && curl -o fire.png localhost:8000/snapshot.png`
+use doomfire::{control::Command, Doomfire};
+use std::env;
+use std::error::Error;
+use std::io;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 168;
+const FPS: u64 = 30;
+const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+/// Encodes `frame`, the pixels of `doomfire`, as a PNG.
+fn snapshot(doomfire: &Doomfire, frame: &[u8]) -> Result<Vec<u8>, png::EncodingError> {
+    let mut png = Vec::new();
+    let mut encoder =
+        png::Encoder::new(&mut png, doomfire.width() as u32, doomfire.height() as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(frame)?;
+    Ok(png)
+}
+
+/// Parses the body of `request` as a number.
+fn value(request: &mut Request) -> Option<f32> {
+    let mut body = String::new();
+    request.as_reader().read_to_string(&mut body).ok()?;
+    body.trim().parse().ok()
+}
+
+/// Handles `request` and responds to it.
+fn handle(mut request: Request, doomfire: &mut Doomfire, frame: &mut [u8]) -> io::Result<()> {
+    let command = match (request.method(), request.url()) {
+        (Method::Get, "/snapshot.png") => {
+            doomfire.draw(frame);
+            let response = match snapshot(doomfire, frame) {
+                Ok(png) => Response::from_data(png).with_header(
+                    Header::from_bytes(&b"Content-Type"[..], &b"image/png"[..]).unwrap(),
+                ),
+                Err(e) => Response::from_string(e.to_string()).with_status_code(500),
+            };
+            return request.respond(response);
+        }
+        (Method::Post, "/ignite") => Some(Command::Ignite),
+        (Method::Post, "/extinguish") => Some(Command::Extinguish),
+        (Method::Post, "/wind") => value(&mut request).map(Command::Wind),
+        (Method::Post, "/intensity") => value(&mut request).map(Command::Intensity),
+        _ => return request.respond(Response::from_string("not found\n").with_status_code(404)),
+    };
+
+    let response = match command.map(|command| doomfire.apply_command(&command)) {
+        Some(Ok(())) => Response::from_string("ok\n"),
+        Some(Err(e)) => Response::from_string(format!("{}\n", e)).with_status_code(400),
+        None => Response::from_string("expected a number\n").with_status_code(400),
+    };
+    request.respond(response)
+}
+
+fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let server = Server::http(&addr)?;
+    println!("Listening on http://{}", addr);
+
+    let mut doomfire = Doomfire::new(WIDTH, HEIGHT);
+    let mut frame = vec![0; WIDTH * HEIGHT * 4];
+    doomfire.ignite();
+
+    let frame_time = Duration::from_millis(1000 / FPS);
+    loop {
+        let start = Instant::now();
+        doomfire.update();
+
+        // Handle requests until it's time for the next frame.
+        while let Some(timeout) = frame_time.checked_sub(start.elapsed()) {
+            match server.recv_timeout(timeout)? {
+                Some(request) => {
+                    // A client going away mid response shouldn't stop the fire.
+                    if let Err(e) = handle(request, &mut doomfire, &mut frame) {
+                        eprintln!("Failed to respond: {}", e);
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
@@ -1,55 +1,396 @@
 //! An implementation of doomfire using `pixels`.
 //!
 //! Press `Space` to extinguish/ignite the fire (Extinguishing is not as immediate as igniting) .
-use doomfire::Doomfire;
-use pixels::{wgpu::Surface, Error, PixelsBuilder, SurfaceTexture};
+//! `Space`, `R` and `Escape` are the default keys, see the settings below.
+//! Press `1` to `9` to switch every fire to the built-in palettes, in the order of `palettes::NAMES`.
+//! Press `R` to start and stop recording the (first) fire to `doomfire-<unix time>.gif`.
+//! Hold `P` and move the mouse to paint emitters, pixels that keep burning, `Backspace` removes all emitters again.
+//! Press `S` to save the (first) fire with the emitters painted on it to the settings file, to burn them next time too.
+//! Pass `--windows <N>` to open several windows, each with its own fire and palette.
+//! Pass `--fullscreen` to cover every monitor with a fire, like a screensaver (`Escape` quits).
+//! Monitors are picked up and dropped as they are plugged in and out,
+//! and with `--shared` all monitors show the same fire instead of one each.
+//!
+//! The fire and the keys are read from `doomfire.toml` in the working directory if it exists, or from `--config <FILE>`.
+//! The `[fire]` table holds the fields of `doomfire::FireConfig`, fields missing from it take the library defaults:
+//! ```toml
+//! [fire]
+//! width = 600
+//! height = 200
+//! palette = "blue_yellow"
+//! horizontal_spread = 0.5
+//!
+//! [keys]
+//! toggle = "Space"
+//! record = "R"
+//! quit = "Escape"
+//! paint = "P"
+//! clear = "Back"
+//! save = "S"
+//! ```
+use clap::{App, Arg};
+use doomfire::{config::PaletteConfig, export::GifSequence, palettes, Doomfire, Emitter, FireConfig, PALETTE};
+use pixels::{wgpu::Surface, Error, Pixels, PixelsBuilder, SurfaceTexture};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use winit::{
     dpi::{PhysicalSize, Size},
     event::{DeviceEvent, ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+    monitor::MonitorHandle,
+    window::{Fullscreen, Window, WindowBuilder},
 };
 
 const HEIGHT: usize = 200;
 const WIDTH: usize = 600;
 const TITLE: &str = "Doomfire";
 const FPS: u64 = 60;
+/// Every fire pixel covers SCALE x SCALE monitor pixels in fullscreen.
+const SCALE: u32 = 4;
+/// How often the monitors are checked for hotplugging in fullscreen.
+const MONITOR_POLL: Duration = Duration::from_secs(1);
+/// Recordings keep every RECORD_EVERY-th frame, GIF viewers slow down anything faster than 50 fps.
+const RECORD_EVERY: u64 = 2;
+/// The radius in fire pixels of the brush painting emitters.
+const BRUSH: f32 = 1.5;
 
-fn main() -> Result<(), Error> {
-    // init window
-    let event_loop = EventLoop::new();
-    /*  For some reason using a logical size throws
-        thread 'main' panicked at 'index out of bounds: the len is 2400 but the index is 2400'
-        later in doomfire's draw() funtion.
-    */
-    //let size: Size = Size::Logical(LogicalSize::new(WIDTH as f64, HEIGHT as f64));
-    let size: Size = Size::Physical(PhysicalSize::new(WIDTH as u32, HEIGHT as u32));
-    let window = WindowBuilder::new()
-        .with_inner_size(size)
-        .with_title(TITLE)
-        .build(&event_loop)
+/// The contents of `doomfire.toml`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct Settings {
+    fire: FireConfig,
+    keys: Keys,
+}
+
+/// A `WIDTH` x `HEIGHT` fire with the default keys.
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            fire: FireConfig {
+                width: WIDTH,
+                height: HEIGHT,
+                ..FireConfig::default()
+            },
+            keys: Keys::default(),
+        }
+    }
+}
+
+/// The keys of the actions, named like winit's `VirtualKeyCode`s.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct Keys {
+    /// Extinguishes or ignites every fire.
+    toggle: VirtualKeyCode,
+    /// Starts or stops recording.
+    record: VirtualKeyCode,
+    /// Quits in fullscreen.
+    quit: VirtualKeyCode,
+    /// Paints emitters with the mouse while held.
+    paint: VirtualKeyCode,
+    /// Removes all emitters.
+    clear: VirtualKeyCode,
+    /// Saves the settings with the emitters.
+    save: VirtualKeyCode,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Keys {
+            toggle: VirtualKeyCode::Space,
+            record: VirtualKeyCode::R,
+            quit: VirtualKeyCode::Escape,
+            paint: VirtualKeyCode::P,
+            clear: VirtualKeyCode::Back,
+            save: VirtualKeyCode::S,
+        }
+    }
+}
+
+/// Reads the settings from `path`, the defaults are used if `required` is false and the file doesn't exist.
+fn load_settings(path: &str, required: bool) -> Result<Settings, String> {
+    let settings = match fs::read_to_string(path) {
+        Ok(settings) => settings,
+        Err(e) if !required && e.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+        Err(e) => return Err(format!("failed to read {}: {}", path, e)),
+    };
+    let settings: Settings = toml::from_str(&settings).map_err(|e| format!("invalid {}: {}", path, e))?;
+    // Catch unknown palettes before opening any window.
+    settings.fire.build().map_err(|e| format!("invalid {}: {}", path, e))?;
+    Ok(settings)
+}
+
+/// Writes `settings` to `path`, replacing what was there.
+fn save_settings(path: &str, settings: &Settings) -> Result<(), String> {
+    // Going through a `Value` puts the tables after the plain values, as TOML wants them.
+    let settings = toml::Value::try_from(settings)
+        .and_then(|settings| toml::to_string(&settings))
+        .map_err(|e| e.to_string())?;
+    fs::write(path, settings).map_err(|e| e.to_string())
+}
+
+/// A window with its own surface and fire.
+struct FireWindow {
+    window: Window,
+    pixels: Pixels,
+    doomfire: Doomfire,
+    /// The monitor covered in fullscreen.
+    monitor: Option<MonitorHandle>,
+    /// Where the cursor is on the fire, `None` when it's outside of the window.
+    cursor: Option<(f32, f32)>,
+    /// Whether the last emitter of the fire was painted with the mouse, new strokes are merged into it.
+    painted: bool,
+}
+
+impl FireWindow {
+    /// Opens a window the size of the fire of `config`, or a borderless fullscreen window on `monitor`
+    /// with a fire of `fire_size` (the monitor size divided by `SCALE` if `None`).
+    fn new(
+        target: &EventLoopWindowTarget<()>,
+        title: &str,
+        config: &FireConfig,
+        monitor: Option<MonitorHandle>,
+        fire_size: Option<(usize, usize)>,
+    ) -> Result<FireWindow, Error> {
+        /*  For some reason using a logical size throws
+            thread 'main' panicked at 'index out of bounds: the len is 2400 but the index is 2400'
+            later in doomfire's draw() funtion.
+        */
+        //let size: Size = Size::Logical(LogicalSize::new(WIDTH as f64, HEIGHT as f64));
+        let size: Size = Size::Physical(PhysicalSize::new(config.width as u32, config.height as u32));
+        let mut builder = WindowBuilder::new().with_title(title);
+        builder = match &monitor {
+            Some(monitor) => builder.with_fullscreen(Some(Fullscreen::Borderless(monitor.clone()))),
+            None => builder.with_inner_size(size),
+        };
+        let window = builder.build(target).unwrap();
+        let (width, height) = match (&monitor, fire_size) {
+            (_, Some(fire_size)) => fire_size,
+            (Some(monitor), None) => fire_size_for(monitor),
+            (None, None) => (config.width, config.height),
+        };
+        if monitor.is_some() {
+            window.set_cursor_visible(false);
+        }
+
+        // init pixels
+        let surface = Surface::create(&window);
+        // surface_texture expects physical size, luckily inner_size returns physicial size.
+        let size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(size.width, size.height, surface);
+        // request_adapter_options with LowPower (default I guess) throws exit code: 0xc0000005, STATUS_ACCESS_VIOLATION
+        // fixes a driver issue https://github.com/parasyte/pixels/issues/49
+        // The pixel buffer is scaled up to the surface, they only differ in fullscreen.
+        let pixels = PixelsBuilder::new(width as u32, height as u32, surface_texture)
+            .request_adapter_options(wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+            })
+            .build()?;
+
+        // Every fire has its own random number generator, so no two windows burn the same.
+        // The config was checked when it was loaded.
+        let mut doomfire = FireConfig {
+            width,
+            height,
+            ..config.clone()
+        }
+        .build()
         .unwrap();
+        doomfire.ignite();
 
-    // init pixels
-    let surface = Surface::create(&window);
-    // surface_texture expects physical size, luckily inner_size returns physicial size.
-    let size = window.inner_size();
-    let surface_texture = SurfaceTexture::new(size.width, size.height, surface);
-    // request_adapter_options with LowPower (default I guess) throws exit code: 0xc0000005, STATUS_ACCESS_VIOLATION
-    // fixes a driver issue https://github.com/parasyte/pixels/issues/49
-    let mut pixels = PixelsBuilder::new(size.width, size.height, surface_texture)
-        .request_adapter_options(wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::HighPerformance,
-            compatible_surface: None,
+        Ok(FireWindow {
+            window,
+            pixels,
+            doomfire,
+            monitor,
+            cursor: None,
+            painted: false,
         })
-        .build()?;
+    }
+
+    /// Moves the cursor to `position` in physical pixels.
+    /// Returns the stretch of the fire it moved over, `None` if it's outside of the window.
+    fn move_cursor(&mut self, position: (f32, f32)) -> Option<((f32, f32), (f32, f32))> {
+        let to = match self.pixels.window_pos_to_pixel(position) {
+            Ok((x, y)) => (x as f32, y as f32),
+            Err(_) => {
+                self.cursor = None;
+                return None;
+            }
+        };
+        let from = self.cursor.replace(to).unwrap_or(to);
+        Some((from, to))
+    }
+
+    /// Paints a stroke from `from` to `to` that keeps burning.
+    /// All strokes go into a single emitter on top of those of the config.
+    fn paint(&mut self, from: (f32, f32), to: (f32, f32)) {
+        let stroke = Emitter::polyline(&[from, to], BRUSH, PALETTE.len() - 1);
+        let mut emitters = self.doomfire.emitters().to_vec();
+        match emitters.last_mut() {
+            Some(last) if self.painted => {
+                *last = Emitter::from_cells(last.cells().iter().chain(stroke.cells()).copied(), last.heat())
+            }
+            _ => emitters.push(stroke),
+        }
+        self.doomfire.clear_emitters();
+        for emitter in emitters {
+            self.doomfire.add_emitter(emitter);
+        }
+        self.painted = true;
+    }
+}
+
+/// A GIF being recorded with `R`.
+struct Recording {
+    path: String,
+    sequence: GifSequence<BufWriter<File>>,
+    frames: u64,
+}
+
+impl Recording {
+    /// Starts recording `doomfire` to a file named after the current time.
+    fn start(doomfire: &Doomfire) -> io::Result<Recording> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let path = format!("doomfire-{}.gif", now.as_secs());
+        let file = BufWriter::new(File::create(&path)?);
+        let sequence = GifSequence::new(file, doomfire, (FPS / RECORD_EVERY) as u32)?;
+        Ok(Recording {
+            path,
+            sequence,
+            frames: 0,
+        })
+    }
+
+    /// Records the current frame of `doomfire` if it's one of the kept ones.
+    fn push(&mut self, doomfire: &Doomfire) -> io::Result<()> {
+        if self.frames.is_multiple_of(RECORD_EVERY) {
+            self.sequence.push(doomfire)?;
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Finishes the file.
+    fn stop(self) {
+        match self.sequence.finish() {
+            Ok(_) => println!("Saved {}", self.path),
+            Err(e) => eprintln!("Failed to save {}: {}", self.path, e),
+        }
+    }
+}
+
+/// Returns the size of a fire covering `monitor` with `SCALE` x `SCALE` pixels.
+fn fire_size_for(monitor: &MonitorHandle) -> (usize, usize) {
+    let size = monitor.size();
+    (
+        (size.width / SCALE).max(1) as usize,
+        (size.height / SCALE).max(1) as usize,
+    )
+}
+
+/// Returns the name of the palette switched to with a number key.
+fn palette_key(key_code: VirtualKeyCode) -> Option<&'static str> {
+    const KEYS: [VirtualKeyCode; 9] = [
+        VirtualKeyCode::Key1,
+        VirtualKeyCode::Key2,
+        VirtualKeyCode::Key3,
+        VirtualKeyCode::Key4,
+        VirtualKeyCode::Key5,
+        VirtualKeyCode::Key6,
+        VirtualKeyCode::Key7,
+        VirtualKeyCode::Key8,
+        VirtualKeyCode::Key9,
+    ];
+    let index = KEYS.iter().position(|&k| k == key_code)?;
+    palettes::NAMES.get(index).copied()
+}
+
+fn main() -> Result<(), Error> {
+    let matches = App::new(TITLE)
+        .arg(
+            Arg::with_name("windows")
+                .long("windows")
+                .takes_value(true)
+                .default_value("1")
+                .help("The number of windows to open, each with its own fire and palette"),
+        )
+        .arg(
+            Arg::with_name("fullscreen")
+                .long("fullscreen")
+                .conflicts_with("windows")
+                .help("Covers every monitor with a fire, following monitors as they are plugged in and out"),
+        )
+        .arg(
+            Arg::with_name("shared")
+                .long("shared")
+                .requires("fullscreen")
+                .help("Shows the same fire on every monitor instead of one fire each"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .help("The settings file to read instead of doomfire.toml"),
+        )
+        .get_matches();
+    let settings = match matches.value_of("config") {
+        Some(path) => load_settings(path, true),
+        None => load_settings("doomfire.toml", false),
+    }
+    .unwrap_or_else(|e| clap::Error::with_description(&e, clap::ErrorKind::InvalidValue).exit());
+    let keys = settings.keys;
+    let settings_path = matches.value_of("config").unwrap_or("doomfire.toml").to_string();
+    let fullscreen = matches.is_present("fullscreen");
+    let shared = matches.is_present("shared");
+    let count: usize = matches
+        .value_of("windows")
+        .unwrap()
+        .parse()
+        .unwrap_or_else(|_| {
+            clap::Error::with_description(
+                "the number of windows must be a number",
+                clap::ErrorKind::InvalidValue,
+            )
+            .exit()
+        });
 
-    let mut doomfire = Doomfire::new(WIDTH, HEIGHT);
-    doomfire.ignite();
+    // init windows
+    let event_loop = EventLoop::new();
+    let mut windows = Vec::new();
+    // Shared fires are all the size of the one on the primary monitor, the surfaces scale it to their monitor.
+    let shared_size = if shared {
+        Some(fire_size_for(&event_loop.primary_monitor()))
+    } else {
+        None
+    };
+    if fullscreen {
+        for monitor in event_loop.available_monitors() {
+            windows.push(FireWindow::new(&event_loop, TITLE, &settings.fire, Some(monitor), shared_size)?);
+        }
+    }
+    for i in 0..if fullscreen { 0 } else { count.max(1) } {
+        // The first window burns with the configured palette, the others cycle through the built-in ones.
+        let mut config = settings.fire.clone();
+        if i > 0 {
+            config.palette = PaletteConfig::Named(palettes::NAMES[i % palettes::NAMES.len()].to_string());
+        }
+        let title = match &config.palette {
+            PaletteConfig::Named(name) if count > 1 => format!("{} ({})", TITLE, name),
+            _ => TITLE.to_string(),
+        };
+        windows.push(FireWindow::new(&event_loop, &title, &config, None, None)?);
+    }
+    let fire = settings.fire;
 
-    event_loop.run(move |event, _, control_flow| {
+    let mut last_poll = Instant::now();
+    let mut recording: Option<Recording> = None;
+    let mut painting = false;
+    event_loop.run(move |event, target, control_flow| {
         let start_time = Instant::now();
         *control_flow = ControlFlow::Poll;
 
@@ -57,14 +398,98 @@ fn main() -> Result<(), Error> {
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 window_id,
-            } if window_id == window.id() => *control_flow = ControlFlow::Exit,
-            Event::MainEventsCleared => {
-                doomfire.draw(pixels.get_frame());
-                if pixels.render().map_err(|e| Err::<(), Error>(e)).is_err() {
+            } => {
+                windows.retain(|w| w.window.id() != window_id);
+                if windows.is_empty() {
                     *control_flow = ControlFlow::Exit;
-                    return;
                 }
-                doomfire.update();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                window_id,
+            } => {
+                let stroke = windows
+                    .iter_mut()
+                    .find(|w| w.window.id() == window_id)
+                    .and_then(|w| w.move_cursor((position.x as f32, position.y as f32)));
+                if let Some((from, to)) = stroke.filter(|_| painting) {
+                    // Shared fires are all the same size, paint on the first one which burns for all of them.
+                    let target = if shared {
+                        windows.first_mut()
+                    } else {
+                        windows.iter_mut().find(|w| w.window.id() == window_id)
+                    };
+                    if let Some(fire_window) = target {
+                        fire_window.paint(from, to);
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                window_id,
+            } => {
+                if let Some(fire_window) = windows.iter_mut().find(|w| w.window.id() == window_id) {
+                    fire_window.cursor = None;
+                }
+            }
+            Event::MainEventsCleared => {
+                if fullscreen && last_poll.elapsed() >= MONITOR_POLL {
+                    last_poll = Instant::now();
+                    let monitors: Vec<_> = match windows.first() {
+                        Some(fire_window) => fire_window.window.available_monitors().collect(),
+                        None => Vec::new(),
+                    };
+                    // Close the windows of unplugged monitors and cover the new ones.
+                    windows.retain(|w| w.monitor.as_ref().is_none_or(|m| monitors.contains(m)));
+                    for monitor in monitors {
+                        if !windows.iter().any(|w| w.monitor.as_ref() == Some(&monitor)) {
+                            match FireWindow::new(target, TITLE, &fire, Some(monitor), shared_size) {
+                                Ok(mut fire_window) => {
+                                    if windows.first().is_some_and(|w| !w.doomfire.is_lit) {
+                                        fire_window.doomfire.extinguish();
+                                    }
+                                    windows.push(fire_window);
+                                }
+                                Err(_) => {
+                                    *control_flow = ControlFlow::Exit;
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                    if windows.is_empty() {
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+                }
+
+                if shared {
+                    // The first fire burns for all of them.
+                    if let Some((first, others)) = windows.split_first_mut() {
+                        for fire_window in others.iter_mut() {
+                            first.doomfire.draw(fire_window.pixels.get_frame());
+                        }
+                        first.doomfire.draw(first.pixels.get_frame());
+                        first.doomfire.update();
+                    }
+                } else {
+                    for fire_window in windows.iter_mut() {
+                        fire_window.doomfire.draw(fire_window.pixels.get_frame());
+                        fire_window.doomfire.update();
+                    }
+                }
+                if let (Some(rec), Some(fire_window)) = (recording.as_mut(), windows.first()) {
+                    if let Err(e) = rec.push(&fire_window.doomfire) {
+                        eprintln!("Stopped recording {}: {}", rec.path, e);
+                        recording = None;
+                    }
+                }
+                for fire_window in windows.iter_mut() {
+                    if fire_window.pixels.render().is_err() {
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+                }
 
                 // Max the redraw to 60 fps
                 let end_time = Instant::now();
@@ -86,17 +511,69 @@ fn main() -> Result<(), Error> {
                 device_id: _,
             } => {
                 match key_code {
+                    key if key == keys.quit && fullscreen => *control_flow = ControlFlow::Exit,
                     // Using a match guard to make sure we run code on pressed and not released.
-                    VirtualKeyCode::Space if state == ElementState::Pressed => {
-                        if doomfire.is_lit {
-                            doomfire.extinguish();
-                        } else if !doomfire.is_lit {
-                            doomfire.ignite();
+                    key if key == keys.toggle && state == ElementState::Pressed => {
+                        for FireWindow { doomfire, .. } in windows.iter_mut() {
+                            if doomfire.is_lit {
+                                doomfire.extinguish();
+                            } else if !doomfire.is_lit {
+                                doomfire.ignite();
+                            }
+                        }
+                    }
+                    key if key == keys.paint => painting = state == ElementState::Pressed,
+                    key if key == keys.clear && state == ElementState::Pressed => {
+                        for fire_window in windows.iter_mut() {
+                            fire_window.doomfire.clear_emitters();
+                            fire_window.painted = false;
+                        }
+                    }
+                    key if key == keys.save && state == ElementState::Pressed => {
+                        if let Some(FireWindow { doomfire, .. }) = windows.first() {
+                            // The emitters are in the pixels of this fire, save its size along with them.
+                            let settings = Settings {
+                                fire: FireConfig {
+                                    width: doomfire.width(),
+                                    height: doomfire.height(),
+                                    emitters: doomfire.emitters().to_vec(),
+                                    ..fire.clone()
+                                },
+                                keys,
+                            };
+                            match save_settings(&settings_path, &settings) {
+                                Ok(()) => println!("Saved {}", settings_path),
+                                Err(e) => eprintln!("Failed to save {}: {}", settings_path, e),
+                            }
+                        }
+                    }
+                    key if key == keys.record && state == ElementState::Pressed => match recording.take() {
+                        Some(rec) => rec.stop(),
+                        None => match windows.first().map(|w| Recording::start(&w.doomfire)) {
+                            Some(Ok(rec)) => {
+                                println!("Recording {}", rec.path);
+                                recording = Some(rec);
+                            }
+                            Some(Err(e)) => eprintln!("Failed to start recording: {}", e),
+                            None => (),
+                        },
+                    },
+                    _ if state == ElementState::Pressed => {
+                        if let Some(name) = palette_key(key_code) {
+                            for FireWindow { doomfire, .. } in windows.iter_mut() {
+                                doomfire.set_palette(palettes::by_name(name).unwrap());
+                            }
                         }
                     }
                     _ => (),
                 }
             }
+            // Don't lose a recording that's still running when the last window closes.
+            Event::LoopDestroyed => {
+                if let Some(rec) = recording.take() {
+                    rec.stop();
+                }
+            }
             _ => (),
         }
     });
@@ -0,0 +1,32 @@
+"""The fire in a pygame window, scaled up 3 times. Press space to ignite or extinguish it."""
+import doomfire
+import pygame
+
+WIDTH, HEIGHT, SCALE = 320, 168, 3
+
+pygame.init()
+screen = pygame.display.set_mode((WIDTH * SCALE, HEIGHT * SCALE))
+clock = pygame.time.Clock()
+fire = doomfire.Doomfire(WIDTH, HEIGHT)
+fire.ignite()
+
+running = True
+while running:
+    for event in pygame.event.get():
+        if event.type == pygame.QUIT:
+            running = False
+        elif event.type == pygame.KEYDOWN and event.key == pygame.K_SPACE:
+            if fire.is_lit:
+                fire.extinguish()
+            else:
+                fire.ignite()
+
+    fire.update()
+    # pygame surfaces are indexed (x, y), so the rgb channels of the (y, x, 4) frame are transposed.
+    frame = fire.draw()[:, :, :3].swapaxes(0, 1)
+    surface = pygame.surfarray.make_surface(frame)
+    screen.blit(pygame.transform.scale(surface, screen.get_size()), (0, 0))
+    pygame.display.flip()
+    clock.tick(60)
+
+pygame.quit()
@@ -0,0 +1,115 @@
+//! The doomfire for Python, built into a wheel with maturin. Frames are numpy arrays, ready for pygame,
+//! matplotlib or Pillow.
+//!
+//! ```python
+//! import doomfire
+//!
+//! fire = doomfire.Doomfire(320, 168)
+//! fire.ignite()
+//! # Every frame:
+//! fire.update()
+//! frame = fire.draw()  # (168, 320, 4) uint8 rgba
+//! ```
+use numpy::{PyArray1, PyArray2, PyArray3, PyArrayMethods};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// A fire, see `doomfire::Doomfire`. Fires aren't `Sync`, so they can only be used from the thread creating them.
+#[pyclass(module = "doomfire", unsendable)]
+struct Doomfire {
+    fire: doomfire::Doomfire,
+    // Drawn into and copied to a new array for every `draw()`.
+    frame: Vec<u8>,
+}
+
+#[pymethods]
+impl Doomfire {
+    /// Returns a new (not yet ignited) fire of `width` x `height` pixels.
+    /// Fires with a `seed` burn the same as every other fire with that seed, see `Doomfire::from_seed()`.
+    #[new]
+    #[pyo3(signature = (width, height, seed = None))]
+    fn new(width: usize, height: usize, seed: Option<u64>) -> Doomfire {
+        let fire = match seed {
+            Some(seed) => doomfire::Doomfire::from_seed(width, height, seed),
+            None => doomfire::Doomfire::new(width, height),
+        };
+        Doomfire {
+            fire,
+            frame: vec![0; width * height * 4],
+        }
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.fire.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.fire.height()
+    }
+
+    /// Whether the fire is burning, see `ignite()` and `extinguish()`.
+    #[getter]
+    fn is_lit(&self) -> bool {
+        self.fire.is_lit
+    }
+
+    fn ignite(&mut self) {
+        self.fire.ignite();
+    }
+
+    fn extinguish(&mut self) {
+        self.fire.extinguish();
+    }
+
+    /// See `Doomfire::set_wind()`.
+    fn set_wind(&mut self, wind: f32) {
+        self.fire.set_wind(wind);
+    }
+
+    /// See `Doomfire::set_intensity()`.
+    fn set_intensity(&mut self, intensity: f32) {
+        self.fire.set_intensity(intensity);
+    }
+
+    /// Switches to the palette with this name in `doomfire::palettes`, raises `ValueError` for unknown names.
+    fn set_palette(&mut self, name: &str) -> PyResult<()> {
+        let palette = doomfire::palettes::by_name(name).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "unknown palette {:?}, expected one of {}",
+                name,
+                doomfire::palettes::NAMES.join(", ")
+            ))
+        })?;
+        self.fire.set_palette(palette);
+        Ok(())
+    }
+
+    /// Updates the fire a single step.
+    fn update(&mut self) {
+        self.fire.update();
+    }
+
+    /// Returns the fire as a `(height, width, 4)` uint8 array of rgba pixels.
+    fn draw<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        self.fire.draw(&mut self.frame);
+        PyArray1::from_slice(py, &self.frame).reshape([
+            self.fire.height(),
+            self.fire.width(),
+            4,
+        ])
+    }
+
+    /// Returns the heat (palette index, 0 to 36) of every pixel as a `(height, width)` uint8 array.
+    fn heat<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<u8>>> {
+        PyArray1::from_slice(py, self.fire.heat()).reshape([self.fire.height(), self.fire.width()])
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "doomfire")]
+fn doomfire_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Doomfire>()?;
+    module.add("PALETTES", doomfire::palettes::NAMES.to_vec())?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! An implementation of doomfire using `raylib`, updating a texture every frame and drawing it scaled up.
+//!
+//! Press `Space` to extinguish/ignite the fire.
+use doomfire::Doomfire;
+use raylib::{error::Error, prelude::*};
+
+const WIDTH: usize = 300;
+const HEIGHT: usize = 200;
+/// Every fire pixel is drawn as SCALE x SCALE screen pixels.
+const SCALE: i32 = 2;
+const FPS: u32 = 60;
+
+fn main() -> Result<(), Error> {
+    let (mut rl, thread) = raylib::init()
+        .size(WIDTH as i32 * SCALE, HEIGHT as i32 * SCALE)
+        .title("Doomfire")
+        .build();
+    rl.set_target_fps(FPS);
+
+    // A texture in the same rgba format as the frame the fire draws to.
+    let image = Image::gen_image_color(WIDTH as i32, HEIGHT as i32, Color::BLACK);
+    let mut texture = rl.load_texture_from_image(&thread, &image)?;
+    let mut frame = vec![0; WIDTH * HEIGHT * 4];
+
+    let mut doomfire = Doomfire::new(WIDTH, HEIGHT);
+    doomfire.ignite();
+
+    while !rl.window_should_close() {
+        if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+            if doomfire.is_lit {
+                doomfire.extinguish();
+            } else {
+                doomfire.ignite();
+            }
+        }
+
+        doomfire.update_and_draw(&mut frame);
+        texture.update_texture(&frame)?;
+
+        let mut d = rl.begin_drawing(&thread);
+        d.clear_background(Color::BLACK);
+        d.draw_texture_ex(&texture, Vector2::new(0.0, 0.0), 0.0, SCALE as f32, Color::WHITE);
+    }
+    Ok(())
+}
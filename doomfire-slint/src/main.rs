@@ -0,0 +1,78 @@
+//! An implementation of doomfire inside a Slint user interface, the fire is an `Image` updated by a timer.
+use doomfire::Doomfire;
+use slint::{Image, Rgba8Pixel, SharedPixelBuffer, Timer, TimerMode};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+const WIDTH: u32 = 300;
+const HEIGHT: u32 = 200;
+const FPS: u64 = 60;
+
+slint::slint! {
+    import { Button } from "std-widgets.slint";
+
+    export component FireWindow inherits Window {
+        title: "Doomfire";
+        preferred-width: 600px;
+        preferred-height: 440px;
+
+        in property <image> fire;
+        in property <bool> lit;
+        callback toggle();
+
+        VerticalLayout {
+            Image {
+                source: root.fire;
+                // Keep the blocky look of the original when scaling up.
+                image-rendering: pixelated;
+                vertical-stretch: 1;
+            }
+            HorizontalLayout {
+                alignment: center;
+                padding: 8px;
+                Button {
+                    text: root.lit ? "Extinguish" : "Ignite";
+                    clicked => { root.toggle(); }
+                }
+            }
+        }
+    }
+}
+
+fn main() -> Result<(), slint::PlatformError> {
+    let ui = FireWindow::new()?;
+    let doomfire = Rc::new(RefCell::new(Doomfire::new(WIDTH as usize, HEIGHT as usize)));
+    doomfire.borrow_mut().ignite();
+    ui.set_lit(true);
+
+    let (fire, weak) = (doomfire.clone(), ui.as_weak());
+    ui.on_toggle(move || {
+        let mut fire = fire.borrow_mut();
+        if fire.is_lit {
+            fire.extinguish();
+        } else {
+            fire.ignite();
+        }
+        if let Some(ui) = weak.upgrade() {
+            ui.set_lit(fire.is_lit);
+        }
+    });
+
+    let mut buffer = SharedPixelBuffer::<Rgba8Pixel>::new(WIDTH, HEIGHT);
+    let weak = ui.as_weak();
+    let timer = Timer::default();
+    timer.start(
+        TimerMode::Repeated,
+        Duration::from_millis(1000 / FPS),
+        move || {
+            // Copies the buffer first if the image shown for the previous frame still shares it.
+            doomfire
+                .borrow_mut()
+                .update_and_draw(buffer.make_mut_bytes());
+            if let Some(ui) = weak.upgrade() {
+                ui.set_fire(Image::from_rgba8(buffer.clone()));
+            }
+        },
+    );
+
+    ui.run()
+}
@@ -0,0 +1,162 @@
+//! A playful system monitor in the terminal: the busier the CPU, the hotter the fire burns,
+//! and the more memory is in use, the higher the flames reach. Loads are read with `sysinfo`,
+//! which doesn't report the GPU, and the status line shows what `Doomfire::analytics()` measures.
+//!
+//! Press `q` or `Escape` to quit.
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use doomfire::Doomfire;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+const FPS: u64 = 30;
+/// How often the loads are measured, sysinfo needs at least `MINIMUM_CPU_UPDATE_INTERVAL` between CPU readings.
+const MEASURE_EVERY: Duration = Duration::from_millis(500);
+
+/// The CPU and memory load, from 0.0 to 1.0.
+#[derive(Default)]
+struct Load {
+    cpu: f32,
+    memory: f32,
+}
+
+impl Load {
+    fn measure(system: &mut System) -> Load {
+        system.refresh_cpu_usage();
+        system.refresh_memory();
+        Load {
+            cpu: system.global_cpu_usage() / 100.0,
+            memory: system.used_memory() as f32 / system.total_memory().max(1) as f32,
+        }
+    }
+}
+
+/// Turns the loads into the heat and height of the fire.
+fn apply(doomfire: &mut Doomfire, load: &Load) {
+    // Even an idle machine keeps a small fire going.
+    doomfire.set_intensity(0.3 + 0.7 * load.cpu);
+    // The draws cool half of the pixels by default, less cooling makes taller flames.
+    doomfire.set_cooling(Some(0.8 - 0.5 * load.memory));
+}
+
+/// Draws the fire above a status line, two pixels per cell with the upper half block `▀`.
+fn render<W: Write>(
+    out: &mut W,
+    doomfire: &Doomfire,
+    frame: &mut [u8],
+    load: &Load,
+) -> io::Result<()> {
+    doomfire.draw(frame);
+    let width = doomfire.width();
+    let rgb = |i: usize| Color::Rgb {
+        r: frame[i * 4],
+        g: frame[i * 4 + 1],
+        b: frame[i * 4 + 2],
+    };
+
+    let mut colors = None;
+    for row in 0..doomfire.height() / 2 {
+        queue!(out, cursor::MoveTo(0, row as u16))?;
+        for x in 0..width {
+            let cell = (rgb(row * 2 * width + x), rgb((row * 2 + 1) * width + x));
+            if colors != Some(cell) {
+                queue!(out, SetForegroundColor(cell.0), SetBackgroundColor(cell.1))?;
+                colors = Some(cell);
+            }
+            queue!(out, Print('▀'))?;
+        }
+    }
+
+    let analytics = doomfire.analytics();
+    let status = format!(
+        " cpu {:3.0}%  memory {:3.0}%  flames {:3} px  energy {:7} ",
+        load.cpu * 100.0,
+        load.memory * 100.0,
+        analytics.max_height(),
+        analytics.total_energy,
+    );
+    queue!(
+        out,
+        ResetColor,
+        cursor::MoveTo(0, (doomfire.height() / 2) as u16),
+        Clear(ClearType::CurrentLine),
+        Print(status)
+    )?;
+    out.flush()
+}
+
+/// Returns the size of a fire filling a terminal of `columns` x `rows` cells, leaving the last row for the status line.
+fn fire_size(columns: u16, rows: u16) -> (usize, usize) {
+    (
+        columns.max(1) as usize,
+        rows.saturating_sub(1).max(1) as usize * 2,
+    )
+}
+
+/// Returns a lit fire filling a terminal of `columns` x `rows` cells.
+fn fire_for(columns: u16, rows: u16) -> Doomfire {
+    let (width, height) = fire_size(columns, rows);
+    let mut doomfire = Doomfire::new(width, height);
+    doomfire.ignite();
+    doomfire
+}
+
+fn run<W: Write>(out: &mut W) -> io::Result<()> {
+    let mut system = System::new();
+    let (columns, rows) = terminal::size()?;
+    let mut doomfire = fire_for(columns, rows);
+    let mut frame = vec![0; doomfire.width() * doomfire.height() * 4];
+    let mut load = Load::measure(&mut system);
+    let mut measured = Instant::now();
+
+    let frame_time = Duration::from_millis(1000 / FPS);
+    loop {
+        let start = Instant::now();
+        if measured.elapsed() >= MEASURE_EVERY {
+            load = Load::measure(&mut system);
+            measured = Instant::now();
+        }
+        apply(&mut doomfire, &load);
+        doomfire.update();
+        render(out, &doomfire, &mut frame, &load)?;
+
+        while let Some(timeout) = frame_time.checked_sub(start.elapsed()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if let KeyCode::Char('q') | KeyCode::Esc = key.code {
+                        return Ok(());
+                    }
+                }
+                Event::Resize(columns, rows) => {
+                    let (width, height) = fire_size(columns, rows);
+                    doomfire.resize(width, height);
+                    frame = vec![0; doomfire.width() * doomfire.height() * 4];
+                    queue!(out, Clear(ClearType::All))?;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, cursor::Hide)?;
+
+    let result = run(&mut out);
+
+    // Restore the terminal even if drawing failed.
+    execute!(out, cursor::Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
@@ -0,0 +1,72 @@
+//! A Tauri app where the Rust side runs the doomfire and streams every frame to the webview as raw bytes
+//! over a channel, the page in `ui/index.html` only puts them on a canvas. No WASM involved.
+//!
+//! Click the fire to extinguish/ignite it, the slider blows it around.
+use doomfire::Doomfire;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::State;
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 168;
+const FPS: u64 = 30;
+
+/// The fire shared between the commands and the thread streaming its frames.
+struct Fire(Arc<Mutex<Doomfire>>);
+
+/// Returns the size of the fire, so the page can size its canvas.
+#[tauri::command]
+fn size() -> (usize, usize) {
+    (WIDTH, HEIGHT)
+}
+
+/// Starts sending a frame of rgba bytes to `frames` every update, until the page goes away.
+#[tauri::command]
+fn stream(fire: State<Fire>, frames: Channel<InvokeResponseBody>) {
+    let fire = fire.0.clone();
+    thread::spawn(move || {
+        let frame_time = Duration::from_millis(1000 / FPS);
+        let mut frame = vec![0; WIDTH * HEIGHT * 4];
+        loop {
+            let start = Instant::now();
+            fire.lock().unwrap().update_and_draw(&mut frame);
+            // A reload or closed window drops the channel, stop streaming to it.
+            if frames.send(InvokeResponseBody::Raw(frame.clone())).is_err() {
+                return;
+            }
+            if let Some(rest) = frame_time.checked_sub(start.elapsed()) {
+                thread::sleep(rest);
+            }
+        }
+    });
+}
+
+/// Ignites an extinguished fire and extinguishes a lit one.
+#[tauri::command]
+fn toggle(fire: State<Fire>) {
+    let mut fire = fire.0.lock().unwrap();
+    if fire.is_lit {
+        fire.extinguish();
+    } else {
+        fire.ignite();
+    }
+}
+
+/// Sets the wind, see `Doomfire::set_wind()`.
+#[tauri::command]
+fn set_wind(fire: State<Fire>, wind: f32) {
+    fire.0.lock().unwrap().set_wind(wind);
+}
+
+fn main() {
+    let mut doomfire = Doomfire::new(WIDTH, HEIGHT);
+    doomfire.ignite();
+
+    tauri::Builder::default()
+        .manage(Fire(Arc::new(Mutex::new(doomfire))))
+        .invoke_handler(tauri::generate_handler![size, stream, toggle, set_wind])
+        .run(tauri::generate_context!())
+        .expect("error while running the doomfire");
+}
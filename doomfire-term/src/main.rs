@@ -0,0 +1,133 @@
+//! An implementation of doomfire in the terminal using `crossterm`,
+//! every character cell shows two fire pixels with the upper half block `▀`.
+//!
+//! The fire fills the terminal and follows it when it is resized.
+//! Click or drag with the left mouse button to add heat and with the right one to cool the fire down.
+//! Press `Space` to extinguish/ignite the fire and `q` or `Escape` to quit.
+use crossterm::{
+    cursor,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
+    execute, queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use doomfire::{Doomfire, Rect};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const FPS: u64 = 30;
+/// The radius in fire pixels of the heat added or removed with the mouse.
+const BRUSH: f32 = 2.5;
+
+/// Draws the fire, the pixels of odd rows as the foreground and those of even rows as the background of a cell.
+fn render<W: Write>(out: &mut W, doomfire: &Doomfire, frame: &mut [u8]) -> io::Result<()> {
+    doomfire.draw(frame);
+    let width = doomfire.width();
+    let rgb = |i: usize| Color::Rgb {
+        r: frame[i * 4],
+        g: frame[i * 4 + 1],
+        b: frame[i * 4 + 2],
+    };
+
+    let mut colors = None;
+    for row in 0..doomfire.height() / 2 {
+        queue!(out, cursor::MoveTo(0, row as u16))?;
+        for x in 0..width {
+            let cell = (rgb(row * 2 * width + x), rgb((row * 2 + 1) * width + x));
+            // Most neighbouring cells share their colors, only send the ones that change.
+            if colors != Some(cell) {
+                queue!(out, SetForegroundColor(cell.0), SetBackgroundColor(cell.1))?;
+                colors = Some(cell);
+            }
+            queue!(out, Print('▀'))?;
+        }
+    }
+    queue!(out, ResetColor)?;
+    out.flush()
+}
+
+/// Adds heat under the mouse on left clicks and drags, removes it on right ones.
+fn paint(doomfire: &mut Doomfire, mouse: MouseEvent) {
+    let heat = match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left) => 36,
+        MouseEventKind::Down(MouseButton::Right) | MouseEventKind::Drag(MouseButton::Right) => 0,
+        _ => return,
+    };
+    // A cell is one pixel wide and two high.
+    let center = (mouse.column as f32, mouse.row as f32 * 2.0 + 0.5);
+    doomfire.heat_circle(center, BRUSH, heat);
+}
+
+/// Returns a fire filling a terminal of `columns` x `rows` cells, keeping the bottom of `doomfire` burning.
+fn resize(doomfire: &Doomfire, columns: u16, rows: u16) -> Doomfire {
+    let (width, height) = (columns.max(1) as usize, rows.max(1) as usize * 2);
+    let mut resized = Doomfire::new(width, height);
+    let kept = height.min(doomfire.height());
+    resized.copy_region_from(
+        doomfire,
+        Rect::new(0, doomfire.height() - kept, doomfire.width(), kept),
+        (0, height - kept),
+    );
+    if doomfire.is_lit {
+        resized.ignite();
+    }
+    resized
+}
+
+fn run<W: Write>(out: &mut W) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let mut doomfire = resize(&Doomfire::new(0, 0), columns, rows);
+    let mut frame = vec![0; doomfire.width() * doomfire.height() * 4];
+    doomfire.ignite();
+
+    let frame_time = Duration::from_millis(1000 / FPS);
+    loop {
+        let start = Instant::now();
+        render(out, &doomfire, &mut frame)?;
+        doomfire.update();
+
+        // Handle input until it's time for the next frame.
+        while let Some(timeout) = frame_time.checked_sub(start.elapsed()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+            match event::read()? {
+                // Some terminals also report releases, only act on presses.
+                Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char(' ') => {
+                        if doomfire.is_lit {
+                            doomfire.extinguish();
+                        } else {
+                            doomfire.ignite();
+                        }
+                    }
+                    _ => (),
+                },
+                Event::Mouse(mouse) => paint(&mut doomfire, mouse),
+                Event::Resize(columns, rows) => {
+                    doomfire = resize(&doomfire, columns, rows);
+                    frame = vec![0; doomfire.width() * doomfire.height() * 4];
+                    queue!(out, Clear(ClearType::All))?;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, EnableMouseCapture, cursor::Hide)?;
+
+    let result = run(&mut out);
+
+    // Restore the terminal even if drawing failed.
+    execute!(out, cursor::Show, DisableMouseCapture, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
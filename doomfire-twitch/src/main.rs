@@ -0,0 +1,194 @@
+//! A fire for stream overlays that the chat of a Twitch channel plays with, capture the window in OBS and key out the black.
+//! The chat is read anonymously over Twitch's IRC interface, no account or token needed.
+//!
+//! Run it with `cargo run --release -p doomfire-twitch -- <channel>`, chatters can then send
+//! - `!fire` for a burst of heat, every chatter has their own spot along the bottom,
+//! - `!ignite` and `!extinguish`,
+//! - `!wind <-1.0 to 1.0>`,
+//! - `!palette <name>` with one of the names in `doomfire::palettes::NAMES`.
+//!
+//! Redeeming any channel points reward sets off a big burst and relights the fire.
+use doomfire::{control::Command, Doomfire};
+use pixels::{wgpu::Surface, Error, PixelsBuilder, SurfaceTexture};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+use winit::{
+    dpi::PhysicalSize,
+    event::{Event, StartCause, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    window::WindowBuilder,
+};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 168;
+/// Every fire pixel covers SCALE x SCALE window pixels.
+const SCALE: usize = 3;
+const FPS: u64 = 30;
+const CHAT: &str = "irc.chat.twitch.tv:6667";
+/// Wait this long before connecting again after losing the chat.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// The radius in pixels of a `!fire` burst and of a redeemed reward.
+const BURST: f32 = 6.0;
+const REWARD: f32 = 24.0;
+
+/// A chat message.
+struct Message<'a> {
+    nick: &'a str,
+    text: &'a str,
+    /// Whether the message redeems a channel points reward.
+    redeemed: bool,
+}
+
+/// Returns the chat message in a line from the chat server, `None` for anything else.
+/// Messages look like `@tags :nick!nick@nick.tmi.twitch.tv PRIVMSG #channel :text`, the tags being there
+/// because they are requested when joining.
+fn message(line: &str) -> Option<Message<'_>> {
+    let (tags, rest) = match line.strip_prefix('@') {
+        Some(tagged) => tagged.split_once(' ')?,
+        None => ("", line),
+    };
+    let (prefix, rest) = rest.strip_prefix(':')?.split_once(' ')?;
+    let (_, text) = rest.strip_prefix("PRIVMSG ")?.split_once(" :")?;
+    Some(Message {
+        nick: prefix.split('!').next()?,
+        text,
+        redeemed: tags
+            .split(';')
+            .any(|tag| tag.starts_with("custom-reward-id=")),
+    })
+}
+
+/// Turns a chat message into the commands it asks for.
+fn commands(message: &Message) -> Vec<Command> {
+    // Every chatter always bursts at the same spot.
+    let mut hasher = DefaultHasher::new();
+    message.nick.hash(&mut hasher);
+    let x = (hasher.finish() % WIDTH as u64) as f32;
+    let y = (HEIGHT - 1) as f32;
+
+    if message.redeemed {
+        return vec![
+            Command::Ignite,
+            Command::HeatCircle {
+                center: (x, y),
+                radius: REWARD,
+                heat: 36,
+            },
+        ];
+    }
+    let mut words = message.text.split_whitespace();
+    let command = match (words.next(), words.next()) {
+        (Some("!fire"), _) => Command::HeatCircle {
+            center: (x, y),
+            radius: BURST,
+            heat: 36,
+        },
+        (Some("!ignite"), _) => Command::Ignite,
+        (Some("!extinguish"), _) => Command::Extinguish,
+        (Some("!wind"), Some(wind)) => match wind.parse::<f32>() {
+            Ok(wind) if wind.is_finite() => Command::Wind(wind.clamp(-1.0, 1.0)),
+            _ => return Vec::new(),
+        },
+        (Some("!palette"), Some(name)) => Command::Palette(name.to_lowercase()),
+        _ => return Vec::new(),
+    };
+    vec![command]
+}
+
+/// Joins the chat of `channel` and sends the commands of its messages until the connection breaks.
+fn listen(channel: &str, commands_to: &Sender<Command>) -> io::Result<()> {
+    let mut stream = TcpStream::connect(CHAT)?;
+    // Nicks starting with justinfan may read the chat without logging in.
+    write!(
+        stream,
+        "CAP REQ :twitch.tv/tags\r\nNICK justinfan{}\r\nJOIN #{}\r\n",
+        std::process::id(),
+        channel.to_lowercase()
+    )?;
+    let mut lines = BufReader::new(stream.try_clone()?).lines();
+    while let Some(line) = lines.next().transpose()? {
+        if let Some(server) = line.strip_prefix("PING ") {
+            write!(stream, "PONG {}\r\n", server)?;
+        } else if let Some(message) = message(&line) {
+            for command in commands(&message) {
+                if commands_to.send(command).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Follows the chat of `channel` on a thread, reconnecting whenever it is lost.
+fn follow(channel: String) -> Receiver<Command> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || loop {
+        if let Err(e) = listen(&channel, &sender) {
+            eprintln!("Lost the chat of {}: {}", channel, e);
+        }
+        thread::sleep(RECONNECT_DELAY);
+    });
+    receiver
+}
+
+fn main() -> Result<(), Error> {
+    let channel = match std::env::args().nth(1) {
+        Some(channel) => channel,
+        None => {
+            eprintln!("usage: doomfire-twitch <channel>");
+            std::process::exit(2);
+        }
+    };
+    let chat = follow(channel);
+
+    let event_loop = EventLoop::new();
+    let window = WindowBuilder::new()
+        .with_title("doomfire")
+        .with_inner_size(PhysicalSize::new(
+            (WIDTH * SCALE) as u32,
+            (HEIGHT * SCALE) as u32,
+        ))
+        .build(&event_loop)
+        .unwrap();
+    let surface = Surface::create(&window);
+    let size = window.inner_size();
+    let surface_texture = SurfaceTexture::new(size.width, size.height, surface);
+    // The high performance adapter avoids a driver issue https://github.com/parasyte/pixels/issues/49
+    let mut pixels = PixelsBuilder::new(WIDTH as u32, HEIGHT as u32, surface_texture)
+        .request_adapter_options(wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+        })
+        .build()?;
+
+    let mut doomfire = Doomfire::new(WIDTH, HEIGHT);
+    doomfire.ignite();
+
+    let frame_time = Duration::from_millis(1000 / FPS);
+    event_loop.run(move |event, _, control_flow| match event {
+        Event::NewEvents(StartCause::Init)
+        | Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+            for command in chat.try_iter() {
+                // Unknown palette names are chatters making typos, nothing to act on.
+                let _ = doomfire.apply_command(&command);
+            }
+            doomfire.update_and_draw(pixels.get_frame());
+            if pixels.render().is_err() {
+                *control_flow = ControlFlow::Exit;
+                return;
+            }
+            *control_flow = ControlFlow::WaitUntil(Instant::now() + frame_time);
+        }
+        Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        } => *control_flow = ControlFlow::Exit,
+        _ => (),
+    });
+}
@@ -0,0 +1,79 @@
+// Renders the doomfire into a texture on the renderer of this GameObject, e.g. a quad.
+// Needs the doomfire_unity native plugin in Assets/Plugins, see doomfire-unity/src/lib.rs.
+using System;
+using System.Runtime.InteropServices;
+using UnityEngine;
+
+[RequireComponent(typeof(Renderer))]
+public class Doomfire : MonoBehaviour
+{
+    const string Plugin = "doomfire_unity";
+
+    [DllImport(Plugin)] static extern IntPtr doomfire_new(uint width, uint height);
+    [DllImport(Plugin)] static extern void doomfire_free(IntPtr fire);
+    [DllImport(Plugin)] static extern void doomfire_ignite(IntPtr fire);
+    [DllImport(Plugin)] static extern void doomfire_extinguish(IntPtr fire);
+    [DllImport(Plugin)] static extern void doomfire_set_wind(IntPtr fire, float wind);
+    [DllImport(Plugin)] static extern void doomfire_set_intensity(IntPtr fire, float intensity);
+    [DllImport(Plugin)] static extern void doomfire_update_and_draw(IntPtr fire, byte[] frame, UIntPtr len);
+
+    public int width = 320;
+    public int height = 168;
+    [Tooltip("Updates of the fire per second, the original runs at 30.")]
+    public float updatesPerSecond = 30f;
+    [Range(-1f, 1f)] public float wind = 0f;
+    [Range(0f, 1f)] public float intensity = 1f;
+    public bool isLit = true;
+
+    IntPtr fire;
+    byte[] frame;
+    Texture2D texture;
+    float sinceUpdate;
+    bool wasLit;
+    float lastWind = float.NaN, lastIntensity = float.NaN;
+
+    void OnEnable()
+    {
+        fire = doomfire_new((uint)width, (uint)height);
+        frame = new byte[width * height * 4];
+        // Point filtering keeps the chunky pixels of the original.
+        texture = new Texture2D(width, height, TextureFormat.RGBA32, false) { filterMode = FilterMode.Point };
+
+        var material = GetComponent<Renderer>().material;
+        material.mainTexture = texture;
+        // The fire's rows go down from the top, Unity's textures go up from the bottom.
+        material.mainTextureScale = new Vector2(1f, -1f);
+        material.mainTextureOffset = new Vector2(0f, 1f);
+
+        wasLit = !isLit;
+        lastWind = lastIntensity = float.NaN;
+    }
+
+    void Update()
+    {
+        if (isLit != wasLit)
+        {
+            if (isLit) doomfire_ignite(fire); else doomfire_extinguish(fire);
+            wasLit = isLit;
+        }
+        // Setting the intensity refills the bottom row, only pass on changes.
+        if (wind != lastWind) doomfire_set_wind(fire, lastWind = wind);
+        if (intensity != lastIntensity) doomfire_set_intensity(fire, lastIntensity = intensity);
+
+        // Update at a fixed rate, whatever the frame rate of the game.
+        sinceUpdate += Time.deltaTime;
+        if (sinceUpdate < 1f / updatesPerSecond) return;
+        sinceUpdate = 0f;
+
+        doomfire_update_and_draw(fire, frame, (UIntPtr)frame.Length);
+        texture.LoadRawTextureData(frame);
+        texture.Apply(false);
+    }
+
+    void OnDisable()
+    {
+        doomfire_free(fire);
+        fire = IntPtr.Zero;
+        Destroy(texture);
+    }
+}
@@ -0,0 +1,83 @@
+//! A native plugin exposing the doomfire to Unity, used by the `Doomfire` component in `Unity/Doomfire.cs`.
+//!
+//! Build it with `cargo build --release -p doomfire-unity` and copy `doomfire_unity.dll`, `libdoomfire_unity.so`
+//! or `libdoomfire_unity.dylib` from `target/release` into `Assets/Plugins` of the Unity project.
+//! The fire is owned by the plugin, C# only keeps the pointer returned by `doomfire_new()` and frees it again.
+use doomfire::Doomfire;
+use std::slice;
+
+/// Returns a new fire of `width` x `height` pixels, to be freed with `doomfire_free()`.
+#[no_mangle]
+pub extern "C" fn doomfire_new(width: u32, height: u32) -> *mut Doomfire {
+    Box::into_raw(Box::new(Doomfire::new(width as usize, height as usize)))
+}
+
+/// Frees a fire returned by `doomfire_new()`, passing null does nothing.
+///
+/// # Safety
+/// `fire` must be null or returned by `doomfire_new()`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_free(fire: *mut Doomfire) {
+    if !fire.is_null() {
+        drop(Box::from_raw(fire));
+    }
+}
+
+/// Ignites the fire.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_ignite(fire: *mut Doomfire) {
+    if let Some(fire) = fire.as_mut() {
+        fire.ignite();
+    }
+}
+
+/// Extinguishes the fire.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_extinguish(fire: *mut Doomfire) {
+    if let Some(fire) = fire.as_mut() {
+        fire.extinguish();
+    }
+}
+
+/// Sets the wind, see `Doomfire::set_wind()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_wind(fire: *mut Doomfire, wind: f32) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_wind(wind);
+    }
+}
+
+/// Sets the intensity, see `Doomfire::set_intensity()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_intensity(fire: *mut Doomfire, intensity: f32) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_intensity(intensity);
+    }
+}
+
+/// Updates the fire a step and draws it as rgba to `frame`, a buffer of `len` bytes which must be
+/// `width * height * 4`. Frames of another size are left untouched.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`,
+/// `frame` must be null or point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_update_and_draw(fire: *mut Doomfire, frame: *mut u8, len: usize) {
+    if let Some(fire) = fire.as_mut() {
+        if !frame.is_null() && len == fire.width() * fire.height() * 4 {
+            fire.update_and_draw(slice::from_raw_parts_mut(frame, len));
+        }
+    }
+}
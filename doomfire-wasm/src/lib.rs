@@ -0,0 +1,99 @@
+//! The doomfire for JavaScript, built with `wasm-pack build --target web` and drawn onto a canvas by `index.html`.
+//!
+//! ```js
+//! import init, { Doomfire } from "./pkg/doomfire_wasm.js";
+//!
+//! await init();
+//! const fire = new Doomfire(320, 168);
+//! const image = context.createImageData(fire.width, fire.height);
+//! fire.ignite();
+//! // Every animation frame:
+//! fire.update();
+//! fire.draw(image.data);
+//! context.putImageData(image, 0, 0);
+//! ```
+use js_sys::Uint8ClampedArray;
+use wasm_bindgen::prelude::*;
+
+/// A fire, see `doomfire::Doomfire`.
+#[wasm_bindgen]
+pub struct Doomfire {
+    fire: doomfire::Doomfire,
+    // Drawn into in wasm memory and copied to JavaScript in one go.
+    frame: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Doomfire {
+    /// Returns a new (not yet ignited) fire of `width` x `height` pixels, seeded from `Math.random()`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Doomfire {
+        let seed = (js_sys::Math::random() * u32::MAX as f64) as u64;
+        Doomfire::with_seed(width, height, seed as u32)
+    }
+
+    /// Returns a new fire that burns the same as every other fire with this seed, see `Doomfire::from_seed()`.
+    #[wasm_bindgen(js_name = withSeed)]
+    pub fn with_seed(width: usize, height: usize, seed: u32) -> Doomfire {
+        Doomfire {
+            fire: doomfire::Doomfire::from_seed(width, height, seed as u64),
+            frame: vec![0; width * height * 4],
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.fire.width()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.fire.height()
+    }
+
+    /// Whether the fire is burning, see `ignite()` and `extinguish()`.
+    #[wasm_bindgen(getter, js_name = isLit)]
+    pub fn is_lit(&self) -> bool {
+        self.fire.is_lit
+    }
+
+    pub fn ignite(&mut self) {
+        self.fire.ignite();
+    }
+
+    pub fn extinguish(&mut self) {
+        self.fire.extinguish();
+    }
+
+    /// See `Doomfire::set_wind()`.
+    #[wasm_bindgen(js_name = setWind)]
+    pub fn set_wind(&mut self, wind: f32) {
+        self.fire.set_wind(wind);
+    }
+
+    /// See `Doomfire::set_intensity()`.
+    #[wasm_bindgen(js_name = setIntensity)]
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.fire.set_intensity(intensity);
+    }
+
+    /// Updates the fire a single step.
+    pub fn update(&mut self) {
+        self.fire.update();
+    }
+
+    /// Draws the fire into `frame`, the `data` of an `ImageData` as big as the fire, to show it with `putImageData()`.
+    /// Throws if `frame` isn't `width * height * 4` bytes.
+    pub fn draw(&mut self, frame: &Uint8ClampedArray) -> Result<(), JsValue> {
+        if frame.length() as usize != self.frame.len() {
+            return Err(JsValue::from_str(&format!(
+                "the frame has {} bytes instead of {}",
+                frame.length(),
+                self.frame.len()
+            )));
+        }
+        self.fire.draw(&mut self.frame);
+        frame.copy_from(&self.frame);
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+//! An ambient fire in the terminal that follows the weather outside: the wind blows the flames the way it blows,
+//! and the warmer it is, the hotter the fire burns. The current weather comes from [Open-Meteo](https://open-meteo.com),
+//! which needs no API key.
+//!
+//! Pass the location as `cargo run --release -p doomfire-weather -- <latitude> <longitude>`, it defaults to Brussels.
+//! Press `q` or `Escape` to quit.
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use doomfire::Doomfire;
+use serde::Deserialize;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const FPS: u64 = 30;
+/// Open-Meteo updates its current weather every 15 minutes, there's no use asking more often.
+const FETCH_EVERY: Duration = Duration::from_secs(15 * 60);
+/// After a failed fetch, e.g. without network, try again sooner.
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+/// The wind speed in km/h at which the flames are blown over completely.
+const GALE: f32 = 60.0;
+/// The temperatures in °C at which the fire burns the weakest and the hottest.
+const COLD: f32 = -10.0;
+const HOT: f32 = 35.0;
+
+/// The current weather as Open-Meteo reports it.
+#[derive(Deserialize)]
+struct Weather {
+    /// The temperature 2 m above the ground in °C.
+    temperature_2m: f32,
+    /// The wind speed 10 m above the ground in km/h.
+    wind_speed_10m: f32,
+    /// The direction the wind comes from in degrees, 0° from the north and 90° from the east.
+    wind_direction_10m: f32,
+}
+
+#[derive(Deserialize)]
+struct Forecast {
+    current: Weather,
+}
+
+/// Fetches the current weather, failures are returned as their message to show on the status line.
+fn fetch(latitude: f32, longitude: f32) -> Result<Weather, String> {
+    let forecast: Forecast = ureq::get("https://api.open-meteo.com/v1/forecast")
+        .query("latitude", &latitude.to_string())
+        .query("longitude", &longitude.to_string())
+        .query(
+            "current",
+            "temperature_2m,wind_speed_10m,wind_direction_10m",
+        )
+        .call()
+        .map_err(|error| error.to_string())?
+        .into_json()
+        .map_err(|error| error.to_string())?;
+    Ok(forecast.current)
+}
+
+/// Fetches the weather on a thread, so a slow network never stalls the fire.
+fn watch(latitude: f32, longitude: f32) -> Receiver<Result<Weather, String>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || loop {
+        let weather = fetch(latitude, longitude);
+        let wait = if weather.is_ok() {
+            FETCH_EVERY
+        } else {
+            RETRY_AFTER
+        };
+        if sender.send(weather).is_err() {
+            return;
+        }
+        thread::sleep(wait);
+    });
+    receiver
+}
+
+/// Turns the weather into the wind and heat of the fire.
+fn apply(doomfire: &mut Doomfire, weather: &Weather) {
+    // The fire is seen looking north, wind from the east (90°) blows the flames to the left.
+    let from_east = weather.wind_direction_10m.to_radians().sin();
+    let strength = (weather.wind_speed_10m / GALE).min(1.0);
+    doomfire.set_wind(-from_east * strength);
+    // Even on the coldest day there's a small fire going.
+    let warmth = ((weather.temperature_2m - COLD) / (HOT - COLD)).clamp(0.0, 1.0);
+    doomfire.set_intensity(0.3 + 0.7 * warmth);
+}
+
+/// Draws the fire above a status line, two pixels per cell with the upper half block `▀`.
+fn render<W: Write>(
+    out: &mut W,
+    doomfire: &Doomfire,
+    frame: &mut [u8],
+    status: &str,
+) -> io::Result<()> {
+    doomfire.draw(frame);
+    let width = doomfire.width();
+    let rgb = |i: usize| Color::Rgb {
+        r: frame[i * 4],
+        g: frame[i * 4 + 1],
+        b: frame[i * 4 + 2],
+    };
+
+    let mut colors = None;
+    for row in 0..doomfire.height() / 2 {
+        queue!(out, cursor::MoveTo(0, row as u16))?;
+        for x in 0..width {
+            let cell = (rgb(row * 2 * width + x), rgb((row * 2 + 1) * width + x));
+            if colors != Some(cell) {
+                queue!(out, SetForegroundColor(cell.0), SetBackgroundColor(cell.1))?;
+                colors = Some(cell);
+            }
+            queue!(out, Print('▀'))?;
+        }
+    }
+
+    queue!(
+        out,
+        ResetColor,
+        cursor::MoveTo(0, (doomfire.height() / 2) as u16),
+        Clear(ClearType::CurrentLine),
+        Print(status)
+    )?;
+    out.flush()
+}
+
+/// Returns the size of a fire filling a terminal of `columns` x `rows` cells, leaving the last row for the status line.
+fn fire_size(columns: u16, rows: u16) -> (usize, usize) {
+    (
+        columns.max(1) as usize,
+        rows.saturating_sub(1).max(1) as usize * 2,
+    )
+}
+
+/// Returns a lit fire filling a terminal of `columns` x `rows` cells.
+fn fire_for(columns: u16, rows: u16) -> Doomfire {
+    let (width, height) = fire_size(columns, rows);
+    let mut doomfire = Doomfire::new(width, height);
+    doomfire.ignite();
+    doomfire
+}
+
+fn run<W: Write>(out: &mut W, latitude: f32, longitude: f32) -> io::Result<()> {
+    let weather = watch(latitude, longitude);
+    let (columns, rows) = terminal::size()?;
+    let mut doomfire = fire_for(columns, rows);
+    let mut frame = vec![0; doomfire.width() * doomfire.height() * 4];
+    let mut status = String::from(" fetching the weather...");
+
+    let frame_time = Duration::from_millis(1000 / FPS);
+    loop {
+        let start = Instant::now();
+        match weather.try_recv() {
+            Ok(Ok(new)) => {
+                status = format!(
+                    " {:.1} °C  wind {:.0} km/h from {:.0}° ",
+                    new.temperature_2m, new.wind_speed_10m, new.wind_direction_10m
+                );
+                apply(&mut doomfire, &new);
+            }
+            // Keep burning with the last known weather.
+            Ok(Err(error)) => status = format!(" no weather: {} ", error),
+            Err(_) => (),
+        }
+        doomfire.update();
+        render(out, &doomfire, &mut frame, &status)?;
+
+        while let Some(timeout) = frame_time.checked_sub(start.elapsed()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if let KeyCode::Char('q') | KeyCode::Esc = key.code {
+                        return Ok(());
+                    }
+                }
+                Event::Resize(columns, rows) => {
+                    let (width, height) = fire_size(columns, rows);
+                    doomfire.resize(width, height);
+                    frame = vec![0; doomfire.width() * doomfire.height() * 4];
+                    queue!(out, Clear(ClearType::All))?;
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1).map(|arg| arg.parse::<f32>());
+    let (latitude, longitude) = match (args.next(), args.next()) {
+        (None, None) => (50.85, 4.35),
+        (Some(Ok(latitude)), Some(Ok(longitude))) => (latitude, longitude),
+        _ => {
+            eprintln!("usage: doomfire-weather [<latitude> <longitude>]");
+            std::process::exit(2);
+        }
+    };
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, cursor::Hide)?;
+
+    let result = run(&mut out, latitude, longitude);
+
+    // Restore the terminal even if drawing failed.
+    execute!(out, cursor::Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
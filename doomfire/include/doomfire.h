@@ -0,0 +1,168 @@
+#ifndef DOOMFIRE_H
+#define DOOMFIRE_H
+
+/* Generated with cbindgen from src/ffi.rs, don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct Doomfire Doomfire;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns a new (not yet ignited) fire of `width` x `height` pixels, to be freed with `doomfire_free()`.
+ */
+Doomfire *doomfire_new(size_t width, size_t height);
+
+/**
+ * Returns a new fire like `doomfire_new()` that burns the same as every other fire with this seed.
+ */
+Doomfire *doomfire_new_seeded(size_t width, size_t height, uint64_t seed);
+
+/**
+ * Frees a fire returned by `doomfire_new()`.
+ *
+ * # Safety
+ * `fire` must be null or returned by `doomfire_new()`, and not be used afterwards.
+ */
+void doomfire_free(Doomfire *fire);
+
+/**
+ * Returns the width of the fire in pixels, 0 for null.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+size_t doomfire_width(const Doomfire *fire);
+
+/**
+ * Returns the height of the fire in pixels, 0 for null.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+size_t doomfire_height(const Doomfire *fire);
+
+/**
+ * Ignites the fire.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_ignite(Doomfire *fire);
+
+/**
+ * Extinguishes the fire.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_extinguish(Doomfire *fire);
+
+/**
+ * Returns whether the fire is lit, false for null.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+bool doomfire_is_lit(const Doomfire *fire);
+
+/**
+ * Updates the fire a single step.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_update(Doomfire *fire);
+
+/**
+ * Draws the fire as rgba to `frame`, a buffer of `len` bytes which must be `width * height * 4`.
+ * Returns false and leaves the frame untouched if it isn't.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`,
+ * `frame` must be null or point to `len` writable bytes.
+ */
+bool doomfire_draw(const Doomfire *fire, uint8_t *frame, size_t len);
+
+/**
+ * Updates the fire a step and draws it like `doomfire_draw()`, faster than calling both.
+ * The fire isn't updated either if the frame has the wrong size.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`,
+ * `frame` must be null or point to `len` writable bytes.
+ */
+bool doomfire_update_and_draw(Doomfire *fire, uint8_t *frame, size_t len);
+
+/**
+ * Sets the wind, see `Doomfire::set_wind()`.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_set_wind(Doomfire *fire, float wind);
+
+/**
+ * Sets the intensity, see `Doomfire::set_intensity()`.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_set_intensity(Doomfire *fire, float intensity);
+
+/**
+ * Sets the probability of a pixel cooling every step, a negative `probability` restores the default,
+ * see `Doomfire::set_cooling()`.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_set_cooling(Doomfire *fire, float probability);
+
+/**
+ * Sets the probabilities of drifting sideways and climbing a row, see `Doomfire::set_spread()`.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_set_spread(Doomfire *fire, float horizontal, float vertical);
+
+/**
+ * Sets whether heat leaving one side of the fire enters on the other side, see `Doomfire::set_wrap()`.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`.
+ */
+void doomfire_set_wrap(Doomfire *fire, bool wrap);
+
+/**
+ * Sets the palette from 37 rgba colors (148 bytes), from the coldest to the hottest.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`,
+ * `colors` must be null or point to 148 readable bytes.
+ */
+void doomfire_set_palette(Doomfire *fire, const uint8_t *colors);
+
+/**
+ * Switches to the palette with this name in `palettes`, e.g. `"blue_yellow"`.
+ * Returns false for unknown names.
+ *
+ * # Safety
+ * `fire` must be null or a live fire returned by `doomfire_new()`,
+ * `name` must be null or a nul terminated string.
+ */
+bool doomfire_set_palette_by_name(Doomfire *fire, const char *name);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* DOOMFIRE_H */
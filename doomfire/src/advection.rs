@@ -0,0 +1,90 @@
+//! Pushing the heat of a fire around with a velocity field, e.g. by wind zones, fans or characters moving through it.
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Doomfire;
+use alloc::vec::Vec;
+
+/// Returns the heat of `heat` at `(x, y)` interpolated between the 4 surrounding pixels.
+/// Pixels outside of the fire are cold, unless `wrap` brings them in from the other side.
+fn sample(heat: &[u8], width: usize, height: usize, wrap: bool, x: f32, y: f32) -> u8 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+    let at = |x: isize, y: isize| {
+        let x = if wrap {
+            x.rem_euclid(width as isize)
+        } else {
+            x
+        };
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            0.0
+        } else {
+            heat[y as usize * width + x as usize] as f32
+        }
+    };
+    let (x0, y0) = (x0 as isize, y0 as isize);
+    let top = at(x0, y0) * (1.0 - tx) + at(x0 + 1, y0) * tx;
+    let bottom = at(x0, y0 + 1) * (1.0 - tx) + at(x0 + 1, y0 + 1) * tx;
+    (top * (1.0 - ty) + bottom * ty).round() as u8
+}
+
+impl Doomfire {
+    /// Returns the velocity field heat is pushed around by, see `set_velocity_field()`.
+    pub fn velocity_field(&self) -> Option<&[(f32, f32)]> {
+        self.velocity_field.as_deref()
+    }
+
+    /// Returns the velocity field to change it in place without allocating, e.g. every frame as a character moves.
+    pub fn velocity_field_mut(&mut self) -> Option<&mut [(f32, f32)]> {
+        self.velocity_field.as_deref_mut()
+    }
+
+    /// Pushes the heat around by a velocity in pixels per update (x right, y down) for every pixel,
+    /// row by row starting at the top left like `heat()`, on top of the fire rising.
+    /// Pixels missing at the end of `field` don't move. Pass `None` (the default) to stop pushing the heat.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.ignite();
+    /// // A fan blowing the flames to the right in the left half of the fire.
+    /// let field = (0..600 * 400)
+    ///     .map(|i| if i % 600 < 300 { (2.0, 0.0) } else { (0.0, 0.0) })
+    ///     .collect();
+    /// doomfire.set_velocity_field(Some(field));
+    /// doomfire.update();
+    /// ```
+    pub fn set_velocity_field(&mut self, field: Option<Vec<(f32, f32)>>) {
+        if field.is_some() {
+            // Room for the heat of the update before, so advecting doesn't allocate.
+            self.advected.reserve(self.fire_pixels.capacity());
+        }
+        self.velocity_field = field;
+    }
+
+    /// Moves the heat of every pixel by its velocity, the bottom row of a lit fire stays in place.
+    pub(crate) fn apply_velocity_field(&mut self) {
+        let field = match &self.velocity_field {
+            Some(field) => field,
+            None => return,
+        };
+        self.advected.clear();
+        self.advected.extend_from_slice(&self.fire_pixels);
+        // Heat may be pulled up from anywhere, the next search for the first row with heat starts at the top.
+        self.heat_top = 0;
+
+        let rows = self.height - (self.is_lit && self.height > 0) as usize;
+        for (i, &(vx, vy)) in field.iter().enumerate().take(rows * self.width) {
+            if vx != 0.0 || vy != 0.0 {
+                let (x, y) = ((i % self.width) as f32, (i / self.width) as f32);
+                self.fire_pixels[i] = sample(
+                    &self.advected,
+                    self.width,
+                    self.height,
+                    self.wrap,
+                    x - vx,
+                    y - vy,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Cheap statistics about the current state of the fire, and how much memory it takes.
+use crate::{Doomfire, Emitter, Fireball, PALETTE};
+use alloc::{vec, vec::Vec};
+use core::mem::size_of;
+
+/// A snapshot of the heat distribution, returned by `Doomfire::analytics()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analytics {
+    /// The number of pixels for every heat level (palette index), coldest first.
+    pub histogram: [usize; PALETTE.len()],
+    /// The sum of the heat of all pixels.
+    pub total_energy: usize,
+    /// The height of the flame in every column, measured in pixels from the bottom to the highest burning pixel.
+    /// A column without any heat has a height of 0.
+    pub column_heights: Vec<usize>,
+}
+
+impl Analytics {
+    /// Returns the height of the tallest column.
+    pub fn max_height(&self) -> usize {
+        self.column_heights.iter().copied().max().unwrap_or(0)
+    }
+}
+
+/// The number of bytes taken by a fire and its subsystems, returned by `Doomfire::memory_usage()`.
+///
+/// Buffers are counted by what they have allocated, which can be more than they currently use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// The `Doomfire` struct itself, without its palettes.
+    pub fire: usize,
+    /// The heat of every pixel, and the scratch space and previous frame for stepping it.
+    pub heat: usize,
+    /// The palette and the colors drawn with it after `set_temperature()` and `set_adjustments()`.
+    pub palettes: usize,
+    /// Rain droplets and fireballs.
+    pub particles: usize,
+    /// Emitters, the velocity field and its scratch buffer, the wind map and the cooling curve.
+    pub effects: usize,
+    /// Post processing passes and the buffers they keep between frames.
+    pub postfx: usize,
+    /// Callbacks registered with `on_update()` and friends.
+    pub hooks: usize,
+}
+
+impl MemoryReport {
+    /// Returns the total number of bytes.
+    pub fn total(&self) -> usize {
+        self.fire
+            + self.heat
+            + self.palettes
+            + self.particles
+            + self.effects
+            + self.postfx
+            + self.hooks
+    }
+}
+
+impl Doomfire {
+    /// Returns how many bytes the fire takes, e.g. to budget RAM on embedded or WASM targets.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let doomfire = Doomfire::from_seed(320, 200, 1);
+    /// let report = doomfire.memory_usage();
+    /// // A byte for every pixel, and 8 rows of scratch space.
+    /// assert_eq!(report.heat, 320 * 200 + 8 * 320);
+    /// assert!(report.total() > report.heat);
+    /// ```
+    pub fn memory_usage(&self) -> MemoryReport {
+        let palettes = size_of::<[[u8; 4]; 37]>() * 2;
+        let emitters = self.emitters.capacity() * size_of::<Emitter>()
+            + self
+                .emitters
+                .iter()
+                .map(|emitter| core::mem::size_of_val(emitter.cells()))
+                .sum::<usize>();
+        let velocity_field = self
+            .velocity_field
+            .as_ref()
+            .map_or(0, |field| field.capacity() * size_of::<(f32, f32)>());
+        let cooling_curve = self
+            .cooling_curve
+            .as_ref()
+            .map_or(0, |curve| curve.capacity() * size_of::<f32>());
+
+        MemoryReport {
+            fire: size_of::<Doomfire>() - palettes,
+            heat: self.fire_pixels.capacity() + self.scratch.capacity() + self.previous.capacity(),
+            palettes,
+            particles: self.droplets.capacity() * size_of::<(usize, f32)>()
+                + self.fireballs.capacity() * size_of::<Fireball>(),
+            effects: emitters
+                + velocity_field
+                + self.advected.capacity()
+                + self.wind_map.capacity()
+                + cooling_curve,
+            postfx: self.postfx.memory_usage(),
+            hooks: self.hooks.memory_usage(),
+        }
+    }
+
+    /// Returns a histogram of the heat levels, the total energy and the flame height of every column.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.ignite();
+    /// let analytics = doomfire.analytics();
+    /// assert_eq!(analytics.histogram[36], 600);
+    /// assert_eq!(analytics.max_height(), 1);
+    /// ```
+    pub fn analytics(&self) -> Analytics {
+        let mut histogram = [0; PALETTE.len()];
+        let mut total_energy = 0;
+        let mut column_heights = vec![0; self.width];
+
+        for (y, row) in self.fire_pixels.chunks_exact(self.width).enumerate() {
+            for (x, &heat) in row.iter().enumerate() {
+                histogram[heat as usize] += 1;
+                total_energy += heat as usize;
+                // Rows are visited top to bottom so the first burning pixel is the highest one.
+                if heat > 0 && column_heights[x] == 0 {
+                    column_heights[x] = self.height - y;
+                }
+            }
+        }
+
+        Analytics {
+            histogram,
+            total_energy,
+            column_heights,
+        }
+    }
+
+    /// Returns the height of the tallest flame, measured in pixels from the bottom.
+    /// Cheaper than `analytics()` because it stops at the highest row that contains heat.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// assert_eq!(doomfire.flame_height(), 0);
+    /// doomfire.ignite();
+    /// assert_eq!(doomfire.flame_height(), 1);
+    /// ```
+    pub fn flame_height(&self) -> usize {
+        self.fire_pixels
+            .chunks_exact(self.width)
+            .position(|row| row.iter().any(|&heat| heat > 0))
+            .map_or(0, |y| self.height - y)
+    }
+}
@@ -0,0 +1,122 @@
+//! Crackling fire sound driven by the same simulation as the picture.
+//!
+//! The sound is made of a low rumble (low pass filtered noise) that follows the total heat of the fire
+//! and short high passed noise bursts (the crackles) that are triggered more often the more hot pixels there are.
+//!
+//! # Example
+//! ```
+//! use doomfire::{audio::Crackle, Doomfire};
+//!
+//! let mut doomfire = Doomfire::new(600, 400);
+//! let mut crackle = Crackle::new(44_100);
+//! doomfire.ignite();
+//!
+//! // Normally done in your render loop.
+//! doomfire.update();
+//! crackle.listen(&doomfire);
+//!
+//! // Normally done in your audio callback.
+//! let mut samples = [0.0; 512];
+//! crackle.fill(&mut samples);
+//! assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+//! ```
+use crate::{Doomfire, PALETTE};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+/// Heat from which a pixel counts as hot for triggering crackles.
+const HOT: usize = PALETTE.len() * 2 / 3;
+
+/// Cutoff frequency in Hz of the low pass filter shaping the rumble.
+const RUMBLE_CUTOFF: f32 = 300.0;
+
+/// Time in seconds it takes the loudness and crackle rate to follow a change in the fire.
+const SMOOTHING: f32 = 0.05;
+
+/// A crackling fire synthesizer writing mono `f32` samples in the range `-1.0..=1.0`.
+pub struct Crackle {
+    sample_rate: f32,
+    max_rate: f32,
+    rng: SmallRng,
+    // Targets set by `listen()` and their smoothed values, so sudden changes don't click.
+    level: f32,
+    rate: f32,
+    smooth_level: f32,
+    smooth_rate: f32,
+    smoothing: f32,
+    rumble: f32,
+    rumble_coefficient: f32,
+    // The crackle that is currently sounding.
+    burst: f32,
+    burst_decay: f32,
+    previous_noise: f32,
+}
+
+impl Crackle {
+    /// Returns a new silent synthesizer for the given sample rate in Hz.
+    pub fn new(sample_rate: u32) -> Crackle {
+        let sample_rate = sample_rate as f32;
+        Crackle {
+            sample_rate,
+            max_rate: 60.0,
+            rng: SmallRng::from_entropy(),
+            level: 0.0,
+            rate: 0.0,
+            smooth_level: 0.0,
+            smooth_rate: 0.0,
+            smoothing: 1.0 - (-1.0 / (SMOOTHING * sample_rate)).exp(),
+            rumble: 0.0,
+            rumble_coefficient: 1.0
+                - (-2.0 * std::f32::consts::PI * RUMBLE_CUTOFF / sample_rate).exp(),
+            burst: 0.0,
+            burst_decay: 0.0,
+            previous_noise: 0.0,
+        }
+    }
+
+    /// Sets the number of crackles per second when the fire is as hot as it gets, 60 by default.
+    pub fn set_max_rate(&mut self, crackles_per_second: f32) {
+        self.max_rate = crackles_per_second;
+    }
+
+    /// Samples the heat of the fire to set the loudness and crackle rate.
+    /// Call it after every `update()` of the fire.
+    pub fn listen(&mut self, fire: &Doomfire) {
+        let mut energy = 0;
+        let mut hot = 0;
+        for &heat in fire.fire_pixels.iter() {
+            energy += heat as usize;
+            if heat as usize >= HOT {
+                hot += 1;
+            }
+        }
+
+        let cells = fire.fire_pixels.len().max(1) as f32;
+        self.level = (energy as f32 / (cells * (PALETTE.len() - 1) as f32) * 4.0).min(1.0);
+        // A tall fire has hot pixels in about a tenth of the frame.
+        self.rate = (hot as f32 / cells * 10.0).min(1.0) * self.max_rate;
+    }
+
+    /// Synthesizes the next `samples.len()` samples.
+    pub fn fill(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            self.smooth_level += (self.level - self.smooth_level) * self.smoothing;
+            self.smooth_rate += (self.rate - self.smooth_rate) * self.smoothing;
+
+            let noise = self.rng.gen_range(-1.0, 1.0);
+            self.rumble += (noise - self.rumble) * self.rumble_coefficient;
+
+            if self.rng.gen::<f32>() < self.smooth_rate / self.sample_rate {
+                // Every crackle gets a random loudness and a length between 2 and 12 ms.
+                self.burst = self.rng.gen_range(0.3, 1.0);
+                let length = self.rng.gen_range(0.002, 0.012) * self.sample_rate;
+                self.burst_decay = (-1.0 / length).exp();
+            }
+            // A first order difference is a cheap high pass filter that makes the crackles snappy.
+            let crackle = (noise - self.previous_noise) * 0.5 * self.burst;
+            self.previous_noise = noise;
+            self.burst *= self.burst_decay;
+
+            *sample = (self.smooth_level * (self.rumble * 0.6 + crackle)).clamp(-1.0, 1.0);
+        }
+    }
+}
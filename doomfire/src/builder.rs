@@ -0,0 +1,172 @@
+//! Configuring a new fire in a single expression.
+use crate::{Distribution, Doomfire, Palette, SimulationMode, PALETTE};
+
+/// Configures a fire in a single expression, options that aren't set keep the defaults of `Doomfire::new()`.
+/// See `FireConfig` for settings loaded from a file.
+/// # Examples
+/// ```
+/// # use doomfire::{palettes::BLUE_FIRE, Doomfire};
+/// let doomfire = Doomfire::builder()
+///     .size(320, 200)
+///     .palette(BLUE_FIRE)
+///     .seed(42)
+///     .wind(0.3)
+///     .ignited(true)
+///     .build();
+/// assert_eq!(doomfire.width(), 320);
+/// assert!(doomfire.is_lit);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DoomfireBuilder {
+    width: usize,
+    height: usize,
+    palette: [[u8; 4]; 37],
+    seed: Option<u64>,
+    wrap: bool,
+    distribution: Distribution,
+    mode: SimulationMode,
+    wind: f32,
+    gravity: Option<(f32, f32)>,
+    cooling: Option<f32>,
+    intensity: f32,
+    ignited: bool,
+}
+
+impl Default for DoomfireBuilder {
+    fn default() -> Self {
+        DoomfireBuilder {
+            width: 600,
+            height: 400,
+            palette: PALETTE,
+            seed: None,
+            wrap: false,
+            distribution: Distribution::default(),
+            mode: SimulationMode::default(),
+            wind: 0.0,
+            gravity: None,
+            cooling: None,
+            intensity: 1.0,
+            ignited: false,
+        }
+    }
+}
+
+impl DoomfireBuilder {
+    /// Returns a builder for a 600 x 400 fire, like `Doomfire::default()`.
+    pub fn new() -> DoomfireBuilder {
+        DoomfireBuilder::default()
+    }
+
+    /// Sets the width and height of the fire in pixels.
+    pub fn size(mut self, width: usize, height: usize) -> DoomfireBuilder {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// See `Doomfire::set_palette()`.
+    pub fn palette<P: Palette>(mut self, palette: P) -> DoomfireBuilder {
+        let max_heat = PALETTE.len() - 1;
+        for (heat, color) in self.palette.iter_mut().enumerate() {
+            *color = palette.color(heat, max_heat);
+        }
+        self
+    }
+
+    /// Seeds the random numbers of the fire, so fires built with the same seed and options burn the same.
+    /// Without a seed every fire burns differently, or like the seed 0 without the `std` feature.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let builder = Doomfire::builder().seed(7).ignited(true);
+    /// let (mut a, mut b) = (builder.build(), builder.build());
+    /// for _ in 0..50 {
+    ///     a.update();
+    ///     b.update();
+    /// }
+    /// assert_eq!(a.heat(), b.heat());
+    /// ```
+    pub fn seed(mut self, seed: u64) -> DoomfireBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// See `Doomfire::set_wrap()`.
+    pub fn wrap(mut self, wrap: bool) -> DoomfireBuilder {
+        self.wrap = wrap;
+        self
+    }
+
+    /// See `Doomfire::set_distribution()`.
+    pub fn distribution(mut self, distribution: Distribution) -> DoomfireBuilder {
+        self.distribution = distribution;
+        self
+    }
+
+    /// See `Doomfire::set_simulation_mode()`.
+    pub fn simulation_mode(mut self, mode: SimulationMode) -> DoomfireBuilder {
+        self.mode = mode;
+        self
+    }
+
+    /// See `Doomfire::set_wind()`.
+    pub fn wind(mut self, wind: f32) -> DoomfireBuilder {
+        self.wind = wind;
+        self
+    }
+
+    /// Sets which way is down in the real world, see `Doomfire::set_gravity()`.
+    pub fn gravity(mut self, gravity: (f32, f32)) -> DoomfireBuilder {
+        self.gravity = Some(gravity);
+        self
+    }
+
+    /// Sets how fast the heat decays, see `Doomfire::set_cooling()`.
+    pub fn cooling(mut self, probability: f32) -> DoomfireBuilder {
+        self.cooling = Some(probability);
+        self
+    }
+
+    /// See `Doomfire::set_intensity()`.
+    pub fn intensity(mut self, intensity: f32) -> DoomfireBuilder {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Whether the fire is ignited when it's built, it isn't by default.
+    pub fn ignited(mut self, ignited: bool) -> DoomfireBuilder {
+        self.ignited = ignited;
+        self
+    }
+
+    /// Returns the fire.
+    pub fn build(&self) -> Doomfire {
+        let mut fire = match self.seed {
+            Some(seed) => Doomfire::from_seed(self.width, self.height, seed),
+            #[cfg(feature = "std")]
+            None => Doomfire::new(self.width, self.height),
+            // There is no entropy to seed from without `std`.
+            #[cfg(not(feature = "std"))]
+            None => Doomfire::from_seed(self.width, self.height, 0),
+        };
+        fire.set_palette(self.palette);
+        fire.set_wrap(self.wrap);
+        fire.set_distribution(self.distribution);
+        fire.set_simulation_mode(self.mode);
+        fire.set_wind(self.wind);
+        fire.set_gravity(self.gravity);
+        fire.set_cooling(self.cooling);
+        fire.set_intensity(self.intensity);
+        if self.ignited && self.width > 0 && self.height > 0 {
+            fire.ignite();
+        }
+        fire
+    }
+}
+
+impl Doomfire {
+    /// Returns a builder configuring a new fire, see `DoomfireBuilder`.
+    pub fn builder() -> DoomfireBuilder {
+        DoomfireBuilder::new()
+    }
+}
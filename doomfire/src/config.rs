@@ -0,0 +1,331 @@
+//! All tunables of a fire in a single struct, so applications can expose user editable settings files.
+//!
+//! With the `serde` feature `FireConfig` can be (de)serialized with any serde format,
+//! the `toml`, `json` and `ron` features add helpers to load it from a string or file.
+//!
+//! # Example
+//! ```
+//! use doomfire::{config::PaletteConfig, FireConfig};
+//!
+//! let config = FireConfig {
+//!     width: 320,
+//!     height: 200,
+//!     palette: PaletteConfig::Named("blue_yellow".to_string()),
+//!     ..FireConfig::default()
+//! };
+//! let doomfire = config.build().unwrap();
+//! assert_eq!(doomfire.width(), 320);
+//! ```
+use crate::{palettes, Distribution, Doomfire, Emitter, Rain};
+use std::{error, fmt, io};
+#[cfg(any(feature = "toml", feature = "json", feature = "ron"))]
+use std::{fs, path::Path};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Errors returned while loading a `FireConfig` or building a fire from it.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file could not be read.
+    Io(io::Error),
+    /// The config is not valid TOML or doesn't match `FireConfig`.
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    /// The config is not valid JSON or doesn't match `FireConfig`.
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    /// The config is not valid RON or doesn't match `FireConfig`.
+    #[cfg(feature = "ron")]
+    Ron(ron::error::SpannedError),
+    /// There is no preset with this name in the `PresetRegistry`.
+    #[cfg(feature = "ron")]
+    UnknownPreset(String),
+    /// The config file extension is not one of the enabled formats.
+    UnknownFormat(String),
+    /// There is no palette in `palettes` with this name.
+    UnknownPalette(String),
+    /// A palette has this number of colors instead of 37.
+    PaletteLength(usize),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "could not read config: {}", e),
+            #[cfg(feature = "toml")]
+            ConfigError::Toml(e) => write!(f, "invalid toml config: {}", e),
+            #[cfg(feature = "json")]
+            ConfigError::Json(e) => write!(f, "invalid json config: {}", e),
+            #[cfg(feature = "ron")]
+            ConfigError::Ron(e) => write!(f, "invalid ron config: {}", e),
+            #[cfg(feature = "ron")]
+            ConfigError::UnknownPreset(name) => write!(f, "unknown preset '{}'", name),
+            ConfigError::UnknownFormat(ext) => write!(f, "unsupported config format '{}'", ext),
+            ConfigError::UnknownPalette(name) => write!(f, "unknown palette '{}'", name),
+            ConfigError::PaletteLength(len) => {
+                write!(f, "palette has {} colors, expected 37", len)
+            }
+        }
+    }
+}
+
+impl error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            #[cfg(feature = "toml")]
+            ConfigError::Toml(e) => Some(e),
+            #[cfg(feature = "json")]
+            ConfigError::Json(e) => Some(e),
+            #[cfg(feature = "ron")]
+            ConfigError::Ron(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+/// The palette of a `FireConfig`, either the name of a preset or a list of colors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum PaletteConfig {
+    /// The lower case name of a palette in `palettes`, e.g. `"classic"` or `"blue_yellow"`.
+    Named(String),
+    /// 37 rgba colors, coldest first.
+    Colors(Vec<[u8; 4]>),
+}
+
+impl PaletteConfig {
+    /// Returns the colors of the palette.
+    pub fn resolve(&self) -> Result<[[u8; 4]; 37], ConfigError> {
+        match self {
+            PaletteConfig::Named(name) => {
+                palettes::by_name(name).ok_or_else(|| ConfigError::UnknownPalette(name.clone()))
+            }
+            PaletteConfig::Colors(colors) => {
+                let mut palette = [[0; 4]; 37];
+                if colors.len() != palette.len() {
+                    return Err(ConfigError::PaletteLength(colors.len()));
+                }
+                palette.copy_from_slice(colors);
+                Ok(palette)
+            }
+        }
+    }
+}
+
+impl Default for PaletteConfig {
+    fn default() -> Self {
+        PaletteConfig::Named("classic".to_string())
+    }
+}
+
+/// The tunables of a fire. Missing fields take the values of `FireConfig::default()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct FireConfig {
+    /// The width of the fire in pixels.
+    pub width: usize,
+    /// The height of the fire in pixels.
+    pub height: usize,
+    /// The palette used by `draw()`.
+    pub palette: PaletteConfig,
+    /// See `Doomfire::set_temperature()`.
+    pub temperature: f32,
+    /// See `Doomfire::set_adjustments()`.
+    pub brightness: f32,
+    /// See `Doomfire::set_adjustments()`.
+    pub contrast: f32,
+    /// See `Doomfire::set_adjustments()`.
+    pub gamma: f32,
+    /// See `Doomfire::set_wrap()`.
+    pub wrap: bool,
+    /// See `Doomfire::set_distribution()`.
+    pub distribution: Distribution,
+    /// The probability of drifting sideways, see `Doomfire::set_spread()`.
+    pub horizontal_spread: f32,
+    /// The probability of climbing a row, see `Doomfire::set_spread()`.
+    pub vertical_spread: f32,
+    /// See `Doomfire::set_wind()`.
+    pub wind: f32,
+    /// See `Doomfire::set_intensity()`.
+    pub intensity: f32,
+    /// See `Doomfire::set_ignition_speed()`.
+    pub ignition_speed: f32,
+    /// See `Doomfire::set_flicker()`.
+    pub flicker: f32,
+    /// See `Doomfire::set_cooling()`.
+    pub cooling: Option<f32>,
+    /// See `Doomfire::set_cooling_curve()`.
+    pub cooling_curve: Option<Vec<f32>>,
+    /// The number of rows of the ember floor, see `Doomfire::set_embers()`.
+    pub ember_rows: usize,
+    /// The minimum heat of the ember floor, see `Doomfire::set_embers()`.
+    pub ember_heat: usize,
+    /// See `Doomfire::set_rain()`.
+    pub rain: Option<Rain>,
+    /// The shapes kept burning, see `Doomfire::add_emitter()`.
+    pub emitters: Vec<Emitter>,
+}
+
+impl FireConfig {
+    /// Returns a new (not yet ignited) fire with this configuration.
+    pub fn build(&self) -> Result<Doomfire, ConfigError> {
+        let mut fire = Doomfire::new(self.width, self.height);
+        fire.set_palette(self.palette.resolve()?);
+        fire.set_temperature(self.temperature);
+        fire.set_adjustments(self.brightness, self.contrast, self.gamma);
+        fire.set_wrap(self.wrap);
+        fire.set_distribution(self.distribution);
+        fire.set_spread(self.horizontal_spread, self.vertical_spread);
+        fire.set_wind(self.wind);
+        fire.set_intensity(self.intensity);
+        fire.set_ignition_speed(self.ignition_speed);
+        fire.set_flicker(self.flicker);
+        fire.set_cooling(self.cooling);
+        fire.set_cooling_curve(self.cooling_curve.clone());
+        fire.set_embers(self.ember_rows, self.ember_heat);
+        fire.set_rain(self.rain);
+        for emitter in &self.emitters {
+            fire.add_emitter(emitter.clone());
+        }
+        Ok(fire)
+    }
+
+    /// Applies this configuration to a running fire.
+    /// The heat is kept unless the size differs, then the fire restarts from black (and is reignited if it was lit).
+    /// Callbacks registered with `Doomfire::on_update()` and friends are kept either way.
+    pub fn apply(&self, fire: &mut Doomfire) -> Result<(), ConfigError> {
+        if fire.width() != self.width || fire.height() != self.height {
+            let is_lit = fire.is_lit;
+            let hooks = std::mem::take(&mut fire.hooks);
+            *fire = self.build()?;
+            fire.hooks = hooks;
+            if is_lit {
+                fire.ignite();
+            }
+        } else {
+            fire.set_palette(self.palette.resolve()?);
+            fire.set_temperature(self.temperature);
+            fire.set_adjustments(self.brightness, self.contrast, self.gamma);
+            fire.set_wrap(self.wrap);
+            fire.set_distribution(self.distribution);
+            fire.set_spread(self.horizontal_spread, self.vertical_spread);
+            fire.set_wind(self.wind);
+            fire.set_intensity(self.intensity);
+            fire.set_ignition_speed(self.ignition_speed);
+            fire.set_flicker(self.flicker);
+            fire.set_cooling(self.cooling);
+            fire.set_cooling_curve(self.cooling_curve.clone());
+            fire.set_embers(self.ember_rows, self.ember_heat);
+            if fire.rain() != self.rain {
+                fire.set_rain(self.rain);
+            }
+            if fire.emitters() != &self.emitters[..] {
+                fire.clear_emitters();
+                for emitter in &self.emitters {
+                    fire.add_emitter(emitter.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a TOML config.
+    /// # Examples
+    /// ```
+    /// # use doomfire::FireConfig;
+    /// # use doomfire::Distribution;
+    /// let config = FireConfig::from_toml_str("width = 320\nwrap = true\ndistribution = \"centered\"").unwrap();
+    /// assert_eq!(config.width, 320);
+    /// assert_eq!(config.distribution, Distribution::Centered);
+    /// assert_eq!(config.height, 400);
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(config: &str) -> Result<FireConfig, ConfigError> {
+        toml::from_str(config).map_err(ConfigError::Toml)
+    }
+
+    /// Parses a JSON config.
+    /// # Examples
+    /// ```
+    /// # use doomfire::FireConfig;
+    /// let config = FireConfig::from_json_str(r#"{ "palette": "grayscale", "cooling": 0.4 }"#).unwrap();
+    /// assert_eq!(config.width, 600);
+    /// assert_eq!(config.cooling, Some(0.4));
+    ///
+    /// // Emitters are their cells and heat.
+    /// let config = FireConfig::from_json_str(r#"{ "emitters": [{ "cells": [[3, 2], [1, 2]], "heat": 99 }] }"#).unwrap();
+    /// assert_eq!(config.emitters[0].cells(), &[(1, 2), (3, 2)]);
+    /// assert_eq!(config.emitters[0].heat(), 36);
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json_str(config: &str) -> Result<FireConfig, ConfigError> {
+        serde_json::from_str(config).map_err(ConfigError::Json)
+    }
+
+    /// Parses a RON config.
+    /// # Examples
+    /// ```
+    /// # use doomfire::FireConfig;
+    /// let config = FireConfig::from_ron_str(r#"(width: 320, palette: "cividis")"#).unwrap();
+    /// assert_eq!(config.width, 320);
+    /// ```
+    #[cfg(feature = "ron")]
+    pub fn from_ron_str(config: &str) -> Result<FireConfig, ConfigError> {
+        ron::from_str(config).map_err(ConfigError::Ron)
+    }
+
+    /// Loads a config file, the format is picked from the `.toml`, `.json` or `.ron` extension.
+    #[cfg(any(feature = "toml", feature = "json", feature = "ron"))]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<FireConfig, ConfigError> {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        match extension {
+            #[cfg(feature = "toml")]
+            "toml" => FireConfig::from_toml_str(&fs::read_to_string(path)?),
+            #[cfg(feature = "json")]
+            "json" => FireConfig::from_json_str(&fs::read_to_string(path)?),
+            #[cfg(feature = "ron")]
+            "ron" => FireConfig::from_ron_str(&fs::read_to_string(path)?),
+            _ => Err(ConfigError::UnknownFormat(extension.to_string())),
+        }
+    }
+}
+
+/// Returns the configuration of `Doomfire::default()`.
+impl Default for FireConfig {
+    fn default() -> Self {
+        FireConfig {
+            width: 600,
+            height: 400,
+            palette: PaletteConfig::default(),
+            temperature: 0.0,
+            brightness: 0.0,
+            contrast: 1.0,
+            gamma: 1.0,
+            wrap: false,
+            distribution: Distribution::Classic,
+            horizontal_spread: 1.0,
+            vertical_spread: 1.0,
+            wind: 0.0,
+            intensity: 1.0,
+            ignition_speed: 2.0,
+            flicker: 0.0,
+            cooling: None,
+            cooling_curve: None,
+            ember_rows: 0,
+            ember_heat: 0,
+            rain: None,
+            emitters: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Commands changing a running fire, so scripts and remote controls share a single vocabulary.
+//!
+//! # Example
+//! ```
+//! use doomfire::{control::Command, Doomfire};
+//!
+//! let mut doomfire = Doomfire::new(600, 400);
+//! for command in &[Command::Ignite, Command::Wind(-0.2), Command::Palette("cividis".to_string())] {
+//!     doomfire.apply_command(command).unwrap();
+//! }
+//! assert!(doomfire.is_lit);
+//! ```
+use crate::{config::ConfigError, palettes, Doomfire};
+
+/// A change to a running fire.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// See `Doomfire::ignite()`.
+    Ignite,
+    /// See `Doomfire::extinguish()`.
+    Extinguish,
+    /// See `Doomfire::set_intensity()`.
+    Intensity(f32),
+    /// See `Doomfire::set_wind()`.
+    Wind(f32),
+    /// Switches to the palette with this name in `palettes`.
+    Palette(String),
+    /// See `Doomfire::set_heat()`.
+    Heat {
+        /// The column.
+        x: usize,
+        /// The row.
+        y: usize,
+        /// The heat (palette index).
+        heat: usize,
+    },
+    /// See `Doomfire::heat_circle()`.
+    HeatCircle {
+        /// The center of the circle.
+        center: (f32, f32),
+        /// The radius in pixels.
+        radius: f32,
+        /// The heat (palette index).
+        heat: usize,
+    },
+}
+
+impl Doomfire {
+    /// Applies `command`, failing only for unknown palette names.
+    pub fn apply_command(&mut self, command: &Command) -> Result<(), ConfigError> {
+        match command {
+            Command::Ignite => self.ignite(),
+            Command::Extinguish => self.extinguish(),
+            Command::Intensity(intensity) => self.set_intensity(*intensity),
+            Command::Wind(wind) => self.set_wind(*wind),
+            Command::Palette(name) => self.set_palette(
+                palettes::by_name(name).ok_or_else(|| ConfigError::UnknownPalette(name.clone()))?,
+            ),
+            Command::Heat { x, y, heat } => self.set_heat(*x, *y, *heat),
+            Command::HeatCircle {
+                center,
+                radius,
+                heat,
+            } => self.heat_circle(*center, *radius, *heat),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+//! Compact per frame deltas of the heat buffer, for streaming the fire to thin display clients.
+//!
+//! Every packet starts with a flags byte followed by run length encoded tokens over the XOR of the
+//! new and previous heat of every pixel, row by row:
+//! - `0x00..=0x7F`: skip `n + 1` unchanged pixels.
+//! - `0x80..=0xFF`: the next `(n & 0x7F) + 1` bytes are XORed into the next pixels.
+//!
+//! Pixels after the last token are unchanged. When the `KEYFRAME` flag is set the decoder starts
+//! from an all black frame, so clients can join a running stream.
+//!
+//! # Example
+//! ```
+//! use doomfire::{
+//!     delta::{DeltaDecoder, DeltaEncoder},
+//!     Doomfire,
+//! };
+//!
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
+//! let mut encoder = DeltaEncoder::new(600, 400);
+//! let mut decoder = DeltaDecoder::new(600, 400);
+//! let mut packet = Vec::new();
+//!
+//! doomfire.ignite();
+//! for _ in 0..10 {
+//!     doomfire.update();
+//!     encoder.encode(&doomfire, &mut packet);
+//!     // Send the packet over the wire...
+//!     decoder.decode(&packet).unwrap();
+//! }
+//! assert_eq!(decoder.heat(), doomfire.heat());
+//! ```
+use crate::{Doomfire, PALETTE};
+use alloc::{vec, vec::Vec};
+use core::{error, fmt};
+
+/// Flag set in the first byte of a packet that isn't based on the previous frame.
+pub const KEYFRAME: u8 = 0x01;
+
+/// The maximum number of pixels a single token covers.
+const MAX_RUN: usize = 0x80;
+
+/// Errors returned by `DeltaDecoder::decode()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The packet is empty or a literal run is cut short.
+    Truncated,
+    /// The tokens cover more pixels than the frame has.
+    Overflow,
+    /// A pixel would get a heat above the hottest palette index.
+    Heat,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "delta packet is truncated"),
+            DecodeError::Overflow => write!(f, "delta packet covers more pixels than the frame"),
+            DecodeError::Heat => write!(f, "delta packet heats a pixel above the palette"),
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+/// Encodes frames as deltas of the previously encoded frame.
+pub struct DeltaEncoder {
+    previous: Vec<u8>,
+    keyframe: bool,
+}
+
+impl DeltaEncoder {
+    /// Returns an encoder for a fire with the given width and height, the first packet is a keyframe.
+    pub fn new(width: usize, height: usize) -> DeltaEncoder {
+        DeltaEncoder {
+            previous: vec![0; width * height],
+            keyframe: true,
+        }
+    }
+
+    /// Makes the next packet a keyframe, e.g. when a new client connects.
+    pub fn request_keyframe(&mut self) {
+        self.keyframe = true;
+    }
+
+    /// Replaces the contents of `packet` with the delta of the current frame of `fire`.
+    /// # Panics
+    /// Panics if the size of `fire` differs from the size the encoder was created with.
+    pub fn encode(&mut self, fire: &Doomfire, packet: &mut Vec<u8>) {
+        let heat = fire.heat();
+        assert_eq!(heat.len(), self.previous.len(), "fire size changed");
+
+        packet.clear();
+        if self.keyframe {
+            packet.push(KEYFRAME);
+            self.previous.iter_mut().for_each(|p| *p = 0);
+            self.keyframe = false;
+        } else {
+            packet.push(0);
+        }
+
+        let mut i = 0;
+        while i < heat.len() {
+            if heat[i] == self.previous[i] {
+                let mut run = 1;
+                while i + run < heat.len()
+                    && run < MAX_RUN
+                    && heat[i + run] == self.previous[i + run]
+                {
+                    run += 1;
+                }
+                // A trailing skip is implied.
+                if i + run < heat.len() {
+                    packet.push(run as u8 - 1);
+                }
+                i += run;
+            } else {
+                let mut run = 1;
+                while i + run < heat.len()
+                    && run < MAX_RUN
+                    && heat[i + run] != self.previous[i + run]
+                {
+                    run += 1;
+                }
+                packet.push(0x80 | (run as u8 - 1));
+                for (previous, &h) in self.previous[i..i + run].iter_mut().zip(&heat[i..i + run]) {
+                    packet.push(h ^ *previous);
+                    *previous = h;
+                }
+                i += run;
+            }
+        }
+    }
+}
+
+/// Rebuilds the heat buffer from packets made by a `DeltaEncoder`.
+pub struct DeltaDecoder {
+    width: usize,
+    heat: Vec<u8>,
+}
+
+impl DeltaDecoder {
+    /// Returns a decoder for a fire with the given width and height, starting from an all black frame.
+    pub fn new(width: usize, height: usize) -> DeltaDecoder {
+        DeltaDecoder {
+            width,
+            heat: vec![0; width * height],
+        }
+    }
+
+    /// Applies a packet to the current frame.
+    /// On error the frame is left partially updated until the next keyframe,
+    /// but every pixel keeps a heat within the palette.
+    /// # Examples
+    /// ```
+    /// # use doomfire::delta::{DecodeError, DeltaDecoder, KEYFRAME};
+    /// let mut decoder = DeltaDecoder::new(1, 1);
+    /// assert_eq!(decoder.decode(&[KEYFRAME, 0x80, 200]), Err(DecodeError::Heat));
+    /// assert_eq!(decoder.heat(), &[0]);
+    /// ```
+    pub fn decode(&mut self, packet: &[u8]) -> Result<(), DecodeError> {
+        let (&flags, mut tokens) = packet.split_first().ok_or(DecodeError::Truncated)?;
+        if flags & KEYFRAME != 0 {
+            self.heat.iter_mut().for_each(|h| *h = 0);
+        }
+
+        let mut i = 0;
+        while let Some((&token, rest)) = tokens.split_first() {
+            let run = (token & 0x7F) as usize + 1;
+            if i + run > self.heat.len() {
+                return Err(DecodeError::Overflow);
+            }
+            if token & 0x80 == 0 {
+                tokens = rest;
+            } else {
+                if rest.len() < run {
+                    return Err(DecodeError::Truncated);
+                }
+                let pixels = &mut self.heat[i..i + run];
+                if pixels
+                    .iter()
+                    .zip(&rest[..run])
+                    .any(|(h, x)| (h ^ x) as usize >= PALETTE.len())
+                {
+                    return Err(DecodeError::Heat);
+                }
+                for (h, x) in pixels.iter_mut().zip(&rest[..run]) {
+                    *h ^= x;
+                }
+                tokens = &rest[run..];
+            }
+            i += run;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the decoded heat (palette index) of every pixel, row by row.
+    pub fn heat(&self) -> &[u8] {
+        &self.heat
+    }
+
+    /// Copies the colors of the decoded frame into an rgba pixel buffer, like `Doomfire::draw()`.
+    pub fn draw(&self, frame: &mut [u8], palette: &[[u8; 4]]) {
+        debug_assert_eq!(frame.len(), self.heat.len() * 4);
+        for (pixel, &heat) in frame.chunks_exact_mut(4).zip(self.heat.iter()) {
+            pixel.copy_from_slice(&palette[heat as usize]);
+        }
+    }
+
+    /// Returns the width of the decoded frame in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the decoded frame in pixels.
+    pub fn height(&self) -> usize {
+        self.heat.len().checked_div(self.width).unwrap_or(0)
+    }
+}
@@ -0,0 +1,236 @@
+//! The random draws deciding how every pixel drifts and cools.
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Doomfire;
+use alloc::vec::Vec;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The distribution of the random value from 0 to 3 drawn for every pixel in `update()`.
+///
+/// The value moves the heat 1 pixel right (0), straight up (1), 1 pixel left (2) or 2 pixels left (3),
+/// and odd values cool it down by one. Values closer to the center make straighter, taller flames.
+///
+/// All but `Gaussian` are drawn with integer math only, so the same random numbers give bit-identical fires
+/// on every platform and architecture, e.g. for lockstep multiplayer or synchronized installations.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Distribution {
+    /// The odds of `gen_range(0.0, 3.0).round() & 3` in the original implementation,
+    /// 1 and 2 are twice as likely as 0 and 3 because rounding gives the outer values half the range.
+    /// This is the default.
+    /// ```
+    /// # use doomfire::Distribution;
+    /// # use rand::{rngs::StdRng, SeedableRng};
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// let mut counts = [0; 4];
+    /// for _ in 0..60_000 {
+    ///     counts[Distribution::Classic.sample(&mut rng)] += 1;
+    /// }
+    /// // Around 10000, 20000, 20000 and 10000.
+    /// assert!(counts[0] < counts[1] / 2 + 1000 && counts[3] < counts[2] / 2 + 1000);
+    /// ```
+    #[default]
+    Classic,
+    /// Every value is equally likely, making wider, more chaotic flames.
+    Uniform,
+    /// 1 and 2 are three times as likely as 0 and 3.
+    Centered,
+    /// A normal distribution around the center, rounded and clamped to 0 to 3.
+    /// Smaller `sigma`s make straighter flames, around 1.0 is close to `Classic`.
+    /// This one needs floating point logarithms and cosines, which can differ in the last bit between platforms.
+    Gaussian {
+        /// The standard deviation.
+        sigma: f32,
+    },
+}
+
+impl Distribution {
+    /// Draws a value from 0 to 3.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        match *self {
+            // Sixths of the float range, as rounding rounds 1/6 of it to 0, 2/6 to 1, 2/6 to 2 and 1/6 to 3.
+            Distribution::Classic => match rng.gen_range(0, 6) {
+                0 => 0,
+                1 | 2 => 1,
+                3 | 4 => 2,
+                _ => 3,
+            },
+            Distribution::Uniform => rng.gen_range(0, 4),
+            Distribution::Centered => match rng.gen_range(0, 8) {
+                0 => 0,
+                1..=3 => 1,
+                4..=6 => 2,
+                _ => 3,
+            },
+            Distribution::Gaussian { sigma } => {
+                // Box-Muller, 1.0 - u keeps the logarithm away from 0.
+                let u1 = 1.0 - rng.gen::<f32>();
+                let u2 = rng.gen::<f32>();
+                let z = (-2.0 * u1.ln()).sqrt() * (core::f32::consts::TAU * u2).cos();
+                (1.5 + z * sigma).round().clamp(0.0, 3.0) as usize
+            }
+        }
+    }
+}
+
+impl Doomfire {
+    /// Returns the distribution of the random draws in `update()`, see `set_distribution()`.
+    pub fn distribution(&self) -> Distribution {
+        self.distribution
+    }
+
+    /// Replaces the distribution of the random draws deciding how every pixel drifts and cools.
+    /// `Classic` (the default) keeps the look of the original fire.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Distribution, Doomfire};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_distribution(Distribution::Gaussian { sigma: 0.5 });
+    /// ```
+    pub fn set_distribution(&mut self, distribution: Distribution) {
+        self.distribution = distribution;
+    }
+
+    /// Returns the probabilities of drifting sideways and climbing a row, see `set_spread()`.
+    pub fn spread(&self) -> (f32, f32) {
+        self.spread
+    }
+
+    /// Sets the probability (0.0 to 1.0) that the heat of a pixel drifts sideways and that it climbs a row every update.
+    /// Heat that doesn't drift goes straight up, heat that doesn't climb stays in its row (and still cools down),
+    /// so a low `horizontal` makes tall, narrow flames and a low `vertical` short, wide ones.
+    /// Both default to 1.0, the original fire.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // Candle like flames.
+    /// doomfire.set_spread(0.3, 1.0);
+    /// ```
+    pub fn set_spread(&mut self, horizontal: f32, vertical: f32) {
+        self.spread = (horizontal.clamp(0.0, 1.0), vertical.clamp(0.0, 1.0));
+    }
+
+    /// Returns the strength and direction of the wind, see `set_wind()`.
+    pub fn wind(&self) -> f32 {
+        self.wind
+    }
+
+    /// Blows the flames to the right (positive) or left (negative), from -1.0 to 1.0.
+    /// The strength is the probability that heat drifts a column with the wind instead of by its random draw,
+    /// so 1.0 bends every flame over and 0.0 (the default) is the original, slightly leftward, fire.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // A light breeze to the right.
+    /// doomfire.set_wind(0.3);
+    /// ```
+    pub fn set_wind(&mut self, wind: f32) {
+        self.wind = wind.clamp(-1.0, 1.0);
+    }
+
+    /// Returns the local wind of every column or pixel, see `set_wind_map()`.
+    pub fn wind_map(&self) -> &[i8] {
+        &self.wind_map
+    }
+
+    /// Adds wind to parts of the fire only, e.g. a draft from a doorway in the middle of the screen.
+    /// `map` holds a value per column, or per pixel row by row like `heat()`, where -127 and 127 blow as hard as
+    /// `set_wind()` with -1.0 and 1.0. The local wind adds up with `wind()`.
+    /// Maps of any other length are ignored, pass an empty map (the default) to remove it.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // A draft to the right in the middle third of the fire.
+    /// let map: Vec<i8> = (0..600).map(|x| if (200..400).contains(&x) { 80 } else { 0 }).collect();
+    /// doomfire.set_wind_map(&map);
+    /// ```
+    pub fn set_wind_map(&mut self, map: &[i8]) {
+        self.wind_map.clear();
+        self.wind_map.extend_from_slice(map);
+    }
+
+    /// Returns which way is down, see `set_gravity()`.
+    pub fn gravity(&self) -> Option<(f32, f32)> {
+        self.gravity
+    }
+
+    /// Points the flames away from `gravity`, e.g. the reading of an accelerometer, so they keep burning towards
+    /// the real world's up when a handheld device is rotated. The vector is in the fire's coordinates, x to the right
+    /// and y down, its length doesn't matter and upright is `(0.0, 1.0)`, see `kernel::Params::tilted()`.
+    /// `None` (the default) burns straight up the screen.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // Rotated a bit clockwise, the real world's down is down and to the left on the screen.
+    /// doomfire.set_gravity(Some((-2.5, 9.5)));
+    /// ```
+    pub fn set_gravity(&mut self, gravity: Option<(f32, f32)>) {
+        self.gravity = gravity;
+    }
+
+    /// Returns the probability that a pixel cools down, see `set_cooling()`.
+    pub fn cooling(&self) -> Option<f32> {
+        self.cooling
+    }
+
+    /// Sets the probability (0.0 to 1.0) that the heat of a pixel cools down by one every update.
+    /// Lower values make taller, denser flames.
+    ///
+    /// `None` (the default) is the original fire, cooling whenever the draw of the `Distribution` is odd.
+    /// That is a probability of 0.5 with `Classic` but tied to the sideways drift.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_cooling(Some(0.3));
+    /// ```
+    pub fn set_cooling(&mut self, probability: Option<f32>) {
+        self.cooling = probability.map(|p| p.clamp(0.0, 1.0));
+    }
+
+    /// Returns the cooling multipliers from the bottom row up, see `set_cooling_curve()`.
+    pub fn cooling_curve(&self) -> Option<&[f32]> {
+        self.cooling_curve.as_deref()
+    }
+
+    /// Multiplies the cooling probability of `set_cooling()` (0.5 if it's `None`) by a factor depending on the row,
+    /// `curve[0]` being the factor of the bottom row and the last value that of the top row.
+    /// The curve is stretched over the height of the fire so it doesn't need a value for every row.
+    ///
+    /// Probabilities above 1.0 cool by more than 1, e.g. 2.5 cools by 2 or 3,
+    /// so increasing values make a tight flame cap and a constant curve makes towering flames.
+    /// `None` (the default) cools the same on every row.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // Cool slowly at the bottom and three times as fast at the top.
+    /// doomfire.set_cooling_curve(Some(vec![0.5, 1.0, 1.5]));
+    /// ```
+    pub fn set_cooling_curve(&mut self, curve: Option<Vec<f32>>) {
+        self.cooling_curve = curve.filter(|c| !c.is_empty());
+    }
+
+    /// Sets a cooling curve (see `set_cooling_curve()`) from a function of the height above the bottom,
+    /// from 0.0 for the bottom row to 1.0 for the top row.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_cooling_curve_fn(|height| 1.0 + 4.0 * height * height);
+    /// ```
+    pub fn set_cooling_curve_fn<F: Fn(f32) -> f32>(&mut self, curve: F) {
+        let rows = self.height.max(2);
+        let curve = (0..rows)
+            .map(|row| curve(row as f32 / (rows - 1) as f32))
+            .collect();
+        self.set_cooling_curve(Some(curve));
+    }
+}
@@ -0,0 +1,520 @@
+//! Variants of `Doomfire::draw()` that map the fire onto the frame differently.
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{format::Heat8, Doomfire, Rect, PALETTE};
+use core::ops::Range;
+
+/// 4x4 ordered dithering thresholds, out of 16.
+const BAYER: [[usize; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// How `Doomfire::draw_mono()` decides which pixels are on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mono {
+    /// Pixels hotter than this heat (palette index) are on.
+    Threshold(usize),
+    /// Ordered (Bayer) dithering, hotter areas have more pixels on.
+    Dither,
+}
+
+impl Doomfire {
+    /// Updates the fire a single step and draws it like `draw()`, in a single pass over the heat.
+    /// Every row is drawn as soon as no more heat can move into it, while it's still in the cache,
+    /// instead of traversing the whole heat buffer a second time in `draw()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut pixel_buffer = vec![0; 600 * 400 * 4];
+    /// doomfire.ignite();
+    /// doomfire.update_and_draw(&mut pixel_buffer);
+    ///
+    /// // Same as drawing after updating.
+    /// let mut expected = vec![0; 600 * 400 * 4];
+    /// doomfire.draw(&mut expected);
+    /// assert!(pixel_buffer == expected);
+    /// ```
+    pub fn update_and_draw(&mut self, frame: &mut [u8]) {
+        // The parallel and vectorized paths finish all rows at once, so there are none to draw early.
+        if !self.steps_rows() {
+            self.update();
+            self.draw(frame);
+            return;
+        }
+        self.spread_ignition();
+        self.apply_flicker();
+        self.apply_rain();
+        self.apply_fireballs();
+        self.apply_velocity_field();
+        // Applies the ember floor to every row as it's done, and draws it.
+        let (rows, ember_heat) = self.embers;
+        let ember_start = self.height - rows;
+        let (width, colors) = (self.width, self.colors);
+        self.update_rows(|heat, y| {
+            let row = &mut heat[y * width..(y + 1) * width];
+            if y >= ember_start {
+                row.iter_mut().for_each(|h| *h = (*h).max(ember_heat as u8));
+            }
+            let out = &mut frame[y * width * 4..(y + 1) * width * 4];
+            for (pixel, &h) in out.chunks_exact_mut(4).zip(row.iter()) {
+                pixel.copy_from_slice(&colors[h as usize]);
+            }
+        });
+
+        // Emitters can stamp anywhere, so redraw just their pixels.
+        self.apply_emitters();
+        for emitter in &self.emitters {
+            for &(x, y) in emitter.cells() {
+                if x < self.width && y < self.height {
+                    let i = y * self.width + x;
+                    frame[i * 4..i * 4 + 4]
+                        .copy_from_slice(&self.colors[self.fire_pixels[i] as usize]);
+                }
+            }
+        }
+
+        // The ember floor was applied while drawing the rows.
+        if ember_heat > 0 {
+            self.heated_from(ember_start);
+        }
+        self.find_heat_top();
+
+        self.postfx.apply(frame, self.width, self.height);
+        self.run_update_hooks();
+    }
+
+    /// Draws the fire like `draw()`, skipping the rows at the top that are without heat now and were at the previous
+    /// `draw_dirty()` too, as `frame` still shows them. Most of the frame of a dying fire is skipped this way.
+    ///
+    /// `frame` must be the frame of the previous call, use `draw()` for any other frame.
+    /// Changing the colors or size of the fire draws every row again, and so does every call with post processing
+    /// passes, which may spread colors over the rows without heat.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut frame = vec![0; 600 * 400 * 4];
+    /// doomfire.ignite();
+    /// for i in 0..300 {
+    ///     if i == 100 {
+    ///         doomfire.extinguish();
+    ///     }
+    ///     doomfire.update();
+    ///     doomfire.draw_dirty(&mut frame);
+    /// }
+    /// let mut expected = vec![0; 600 * 400 * 4];
+    /// doomfire.draw(&mut expected);
+    /// assert!(frame == expected);
+    /// ```
+    pub fn draw_dirty(&mut self, frame: &mut [u8]) {
+        if !self.postfx.is_empty() {
+            self.drawn_top = None;
+            return self.draw(frame);
+        }
+        // Tracked by `update()` and everything else heating the fire, searching the heat would cost as much as drawing.
+        let top = self.heat_top;
+        let start = self.drawn_top.map_or(0, |drawn| drawn.min(top)) * self.width;
+        self.drawn_top = Some(top);
+        for (pixel, &heat) in frame[start * 4..]
+            .chunks_exact_mut(4)
+            .zip(&self.fire_pixels[start..])
+        {
+            pixel.copy_from_slice(&self.colors[heat as usize]);
+        }
+    }
+
+    /// Draws the fire twice side by side, the right copy mirrored, into a frame twice as wide as the fire.
+    /// Simulating only half of a symmetric fire (e.g. twin torches framing a menu) halves the cost of `update()`.
+    ///
+    /// With a `seam_jitter` larger than 0 every row of the mirrored half is shifted by up to that many pixels
+    /// so the symmetry is less obvious where both halves meet.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // A 600x400 frame showing a 300x400 fire and its reflection.
+    /// let mut doomfire = Doomfire::from_seed(300, 400, 1);
+    /// let mut pixel_buffer = vec![0; 600 * 400 * 4];
+    /// doomfire.ignite();
+    /// doomfire.update();
+    /// doomfire.draw_mirrored(&mut pixel_buffer, 2);
+    /// ```
+    pub fn draw_mirrored(&self, frame: &mut [u8], seam_jitter: usize) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let width = self.width;
+        for (y, (row, out)) in self
+            .fire_pixels
+            .chunks_exact(width)
+            .zip(frame.chunks_exact_mut(width * 8))
+            .enumerate()
+        {
+            let (left, right) = out.split_at_mut(width * 4);
+            for (pixel, &heat) in left.chunks_exact_mut(4).zip(row.iter()) {
+                pixel.copy_from_slice(&self.colors[heat as usize]);
+            }
+
+            // Derive the shift from the heat at the seam so it changes every frame without needing an rng.
+            let span = 2 * seam_jitter + 1;
+            let shift = (row[width - 1] as usize + y) % span;
+            for (x, pixel) in right.chunks_exact_mut(4).enumerate() {
+                let src = (width - 1 - x + shift)
+                    .saturating_sub(seam_jitter)
+                    .min(width - 1);
+                pixel.copy_from_slice(&self.colors[row[src] as usize]);
+            }
+        }
+    }
+
+    /// Draws the fire `factor` times smaller, averaging every `factor`x`factor` block of pixels, into a frame of
+    /// `width / factor` x `height / factor` pixels. Pixels left over at the right and bottom are dropped, a fire
+    /// smaller than `factor` draws nothing.
+    /// The fire isn't supersampled for you: creating it `factor` times larger than the output (e.g. a 1200x800 fire
+    /// for a 600x400 frame with a factor of 2) costs more cpu but gives smoother, less pixel crawly flames.
+    /// # Panics
+    /// Panics if `factor` is 0.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // Simulate at twice the resolution of the 600x400 frame.
+    /// let mut doomfire = Doomfire::from_seed(1200, 800, 1);
+    /// let mut pixel_buffer = vec![0; 600 * 400 * 4];
+    /// doomfire.ignite();
+    /// doomfire.update();
+    /// doomfire.draw_downsampled(&mut pixel_buffer, 2);
+    /// ```
+    pub fn draw_downsampled(&self, frame: &mut [u8], factor: usize) {
+        assert!(factor > 0, "factor must be at least 1");
+        let (out_width, out_height) = (self.width / factor, self.height / factor);
+        if out_width == 0 || out_height == 0 {
+            return;
+        }
+        let area = (factor * factor) as u32;
+        for (y, out) in frame
+            .chunks_exact_mut(out_width * 4)
+            .take(out_height)
+            .enumerate()
+        {
+            for (x, pixel) in out.chunks_exact_mut(4).enumerate() {
+                let mut sum = [0u32; 4];
+                for row in self.fire_pixels[y * factor * self.width..]
+                    .chunks_exact(self.width)
+                    .take(factor)
+                {
+                    for &heat in &row[x * factor..(x + 1) * factor] {
+                        for (s, &c) in sum.iter_mut().zip(self.colors[heat as usize].iter()) {
+                            *s += c as u32;
+                        }
+                    }
+                }
+                for (p, s) in pixel.iter_mut().zip(sum.iter()) {
+                    *p = (s / area) as u8;
+                }
+            }
+        }
+    }
+
+    /// Draws every pixel of the fire as a `scale_x` x `scale_y` block, for chunky retro pixels on a high resolution frame.
+    /// Different scales give non-square pixels, e.g. 3x2 for a CRT look. The frame is `width * scale_x` pixels wide.
+    /// Every row is drawn once and then copied, which is a lot cheaper than scaling up the output of `draw()`.
+    /// # Panics
+    /// Panics if `scale_x` or `scale_y` is 0.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // A 320x200 fire filling a 1280x800 frame.
+    /// let mut doomfire = Doomfire::from_seed(320, 200, 1);
+    /// let mut pixel_buffer = vec![0; 1280 * 800 * 4];
+    /// doomfire.ignite();
+    /// doomfire.draw_scaled(&mut pixel_buffer, 4, 4);
+    /// assert_eq!(pixel_buffer[pixel_buffer.len() - 4..], doomfire.palette()[36]);
+    /// ```
+    pub fn draw_scaled(&self, frame: &mut [u8], scale_x: usize, scale_y: usize) {
+        assert!(scale_x > 0 && scale_y > 0, "scales must be at least 1");
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let out_row = self.width * scale_x * 4;
+        for (row, out) in self
+            .fire_pixels
+            .chunks_exact(self.width)
+            .zip(frame.chunks_exact_mut(out_row * scale_y))
+        {
+            for (block, &heat) in out[..out_row].chunks_exact_mut(scale_x * 4).zip(row) {
+                for pixel in block.chunks_exact_mut(4) {
+                    pixel.copy_from_slice(&self.colors[heat as usize]);
+                }
+            }
+            for copy in 1..scale_y {
+                out.copy_within(..out_row, copy * out_row);
+            }
+        }
+    }
+
+    /// Draws the fire like `draw()` into a frame whose rows start every `stride` bytes, e.g. a framebuffer with rows
+    /// padded for alignment. The padding is left untouched and may be missing after the last row.
+    /// The post processing passes need a tightly packed frame and are not run.
+    /// # Panics
+    /// Panics if `stride` is smaller than a row, `width * 4` bytes.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // Rows of 600 pixels padded to 2560 bytes.
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut framebuffer = vec![0; 2560 * 400];
+    /// doomfire.ignite();
+    /// doomfire.draw_with_stride(&mut framebuffer, 2560);
+    /// assert_eq!(framebuffer[399 * 2560..][..4], doomfire.palette()[36]);
+    /// assert_eq!(framebuffer[399 * 2560 + 600 * 4], 0);
+    /// ```
+    pub fn draw_with_stride(&self, frame: &mut [u8], stride: usize) {
+        assert!(stride >= self.width * 4, "the stride must hold a row");
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        for (row, out) in self
+            .fire_pixels
+            .chunks_exact(self.width)
+            .zip(frame.chunks_mut(stride))
+        {
+            for (pixel, &heat) in out.chunks_exact_mut(4).zip(row) {
+                pixel.copy_from_slice(&self.colors[heat as usize]);
+            }
+        }
+    }
+
+    /// Draws only the pixels of the fire in `region` into `frame`, which holds `region.width * region.height * 4`
+    /// bytes, e.g. a strip of a large fire for a HUD element. The post processing passes are not run.
+    /// # Panics
+    /// Panics if `region` isn't within the fire or `frame` is too small for it.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Doomfire, Rect};
+    /// // The bottom 600x150 of a 600x400 fire.
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut hud = vec![0; 600 * 150 * 4];
+    /// doomfire.ignite();
+    /// doomfire.draw_region(&mut hud, Rect::new(0, 250, 600, 150));
+    /// assert_eq!(hud[149 * 600 * 4..][..4], doomfire.palette()[36]);
+    /// ```
+    pub fn draw_region(&self, frame: &mut [u8], region: Rect) {
+        assert!(
+            region.x + region.width <= self.width && region.y + region.height <= self.height,
+            "the region must be within the fire"
+        );
+        assert!(
+            frame.len() >= region.width * region.height * 4,
+            "the frame must hold the region"
+        );
+        if region.width == 0 {
+            return;
+        }
+        let rows = self.fire_pixels[region.y * self.width..].chunks(self.width);
+        for (row, out) in rows
+            .zip(frame.chunks_exact_mut(region.width * 4))
+            .take(region.height)
+        {
+            for (pixel, &heat) in out
+                .chunks_exact_mut(4)
+                .zip(&row[region.x..region.x + region.width])
+            {
+                pixel.copy_from_slice(&self.colors[heat as usize]);
+            }
+        }
+    }
+
+    /// Draws only the even rows when `field` is even and only the odd rows when it's odd, leaving the other rows as they were.
+    /// Passing a frame counter as `field` reproduces the look of an interlaced CRT and halves the bytes written per frame.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut pixel_buffer = vec![0; 600 * 400 * 4];
+    /// doomfire.ignite();
+    /// for frame in 0..10 {
+    ///     doomfire.update();
+    ///     doomfire.draw_interlaced(&mut pixel_buffer, frame);
+    /// }
+    /// ```
+    pub fn draw_interlaced(&self, frame: &mut [u8], field: usize) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let parity = field % 2;
+        for (row, out) in self
+            .fire_pixels
+            .chunks_exact(self.width)
+            .zip(frame.chunks_exact_mut(self.width * 4))
+            .skip(parity)
+            .step_by(2)
+        {
+            for (pixel, &heat) in out.chunks_exact_mut(4).zip(row) {
+                pixel.copy_from_slice(&self.colors[heat as usize]);
+            }
+        }
+    }
+
+    /// Draws only the fire's `rows` into `frame`, which holds `rows.len() * width * 4` bytes, for displays fed a strip
+    /// at a time (e.g. over DMA) instead of from a full frame. Rows below the fire are skipped.
+    /// The post processing passes need the whole frame and are not run.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // Double buffered 8 row strips for a 240x135 panel, one is drawn while the other is sent.
+    /// let mut doomfire = Doomfire::from_seed(240, 135, 1);
+    /// let mut strips = [[0; 240 * 8 * 4]; 2];
+    /// doomfire.ignite();
+    /// for (i, start) in (0..135).step_by(8).enumerate() {
+    ///     let strip = &mut strips[i % 2];
+    ///     doomfire.draw_rows(start..start + 8, strip);
+    ///     // Wait for the transfer of the other strip and start sending this one.
+    /// }
+    /// // The last strip only holds the 7 rows left, the bottom one white hot.
+    /// assert_eq!(strips[0][6 * 240 * 4..][..4], doomfire.palette()[36]);
+    /// ```
+    pub fn draw_rows(&self, rows: Range<usize>, frame: &mut [u8]) {
+        let end = rows.end.min(self.height);
+        let start = rows.start.min(end);
+        let heat = &self.fire_pixels[start * self.width..end * self.width];
+        for (pixel, &heat) in frame.chunks_exact_mut(4).zip(heat) {
+            pixel.copy_from_slice(&self.colors[heat as usize]);
+        }
+    }
+
+    /// Draws the fire in strips of as many rows as fit in `buffer`, from the top down, and passes every strip with its
+    /// rows to `strip`, so a frame never has to be allocated. See `draw_rows()`.
+    /// # Panics
+    /// Panics if `buffer` can't hold a single row.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(128, 64, 1);
+    /// let mut buffer = [0; 128 * 16 * 4];
+    /// doomfire.ignite();
+    /// let mut strips = 0;
+    /// doomfire.draw_strips(&mut buffer, |rows, strip| {
+    ///     assert_eq!(strip.len(), rows.len() * 128 * 4);
+    ///     strips += 1;
+    /// });
+    /// assert_eq!(strips, 4);
+    /// ```
+    pub fn draw_strips<F: FnMut(Range<usize>, &[u8])>(&self, buffer: &mut [u8], mut strip: F) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let rows = buffer.len() / (self.width * 4);
+        assert!(rows > 0, "the buffer must hold at least one row");
+        for start in (0..self.height).step_by(rows) {
+            let end = (start + rows).min(self.height);
+            let strip_buffer = &mut buffer[..(end - start) * self.width * 4];
+            self.draw_rows(start..end, strip_buffer);
+            strip(start..end, strip_buffer);
+        }
+    }
+
+    /// Writes the heat of every pixel as a byte, from 0 (coldest) to 255 (hottest), e.g. as a mask or intensity
+    /// texture for a shader doing its own coloring. `frame` holds a byte per pixel.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut mask = vec![0; 600 * 400];
+    /// doomfire.ignite();
+    /// doomfire.draw_heat(&mut mask);
+    /// assert_eq!(mask[399 * 600], 255);
+    /// assert_eq!(mask[0], 0);
+    /// ```
+    pub fn draw_heat(&self, frame: &mut [u8]) {
+        self.draw_into::<Heat8>(frame);
+    }
+
+    /// Draws the fire as 1 bit per pixel, 8 pixels per byte, for OLED, e-paper and flip-dot displays.
+    /// Every row starts on a new byte (`width.div_ceil(8)` bytes per row) and the most significant bit is the leftmost pixel.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Doomfire, Mono};
+    /// // A 128x64 SSD1306 OLED.
+    /// let mut doomfire = Doomfire::from_seed(128, 64, 1);
+    /// let mut buffer = vec![0; 128 / 8 * 64];
+    /// doomfire.ignite();
+    /// doomfire.update();
+    /// doomfire.draw_mono(&mut buffer, Mono::Dither);
+    /// // The white hot bottom row is fully on.
+    /// assert!(buffer[63 * 16..].iter().all(|&b| b == 0xFF));
+    /// ```
+    pub fn draw_mono(&self, frame: &mut [u8], mode: Mono) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let row_bytes = self.width.div_ceil(8);
+        let max_heat = PALETTE.len() - 1;
+        for (y, (row, out)) in self
+            .fire_pixels
+            .chunks_exact(self.width)
+            .zip(frame.chunks_exact_mut(row_bytes))
+            .enumerate()
+        {
+            out.iter_mut().for_each(|b| *b = 0);
+            for (x, &heat) in row.iter().enumerate() {
+                let on = match mode {
+                    Mono::Threshold(threshold) => heat as usize > threshold,
+                    Mono::Dither => heat as usize * 16 > BAYER[y % 4][x % 4] * max_heat,
+                };
+                if on {
+                    out[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+    }
+
+    /// Draws the fire as 16 bits per channel rgba in linear light, for HDR (e.g. `Rgba16Unorm` or scRGB) swapchains.
+    /// SDR white is `65535 / peak`, so the palette keeps its look while the hottest heat levels,
+    /// the flame tips and the bottom row, are boosted up to `peak` times as bright.
+    /// A `peak` of 1.0 draws the regular (linearized) palette.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut frame = vec![0u16; 600 * 400 * 4];
+    /// doomfire.ignite();
+    /// // A display that goes 4 times brighter than SDR white.
+    /// doomfire.draw_hdr(&mut frame, 4.0);
+    /// assert_eq!(frame[399 * 600 * 4..][..4], [65535; 4]);
+    /// // The background is dimmed with the rest of the SDR range.
+    /// assert!(frame[0] < 100);
+    /// ```
+    pub fn draw_hdr(&self, frame: &mut [u16], peak: f32) {
+        // Heat levels above this one glow brighter than SDR white.
+        const HDR_START: usize = 27;
+        let peak = peak.max(1.0);
+        let max_heat = PALETTE.len() - 1;
+
+        let mut colors = [[0u16; 4]; 37];
+        for (heat, (dst, src)) in colors.iter_mut().zip(self.colors.iter()).enumerate() {
+            let boost = if heat > HDR_START {
+                let t = (heat - HDR_START) as f32 / (max_heat - HDR_START) as f32;
+                1.0 + (peak - 1.0) * t * t
+            } else {
+                1.0
+            };
+            for c in 0..3 {
+                let v = srgb_to_linear(src[c]) * boost / peak;
+                dst[c] = (v.min(1.0) * 65535.0).round() as u16;
+            }
+            dst[3] = src[3] as u16 * 257;
+        }
+
+        for (pixel, &heat) in frame.chunks_exact_mut(4).zip(&self.fire_pixels) {
+            pixel.copy_from_slice(&colors[heat as usize]);
+        }
+    }
+}
+
+/// Converts an sRGB encoded channel to linear light, from 0.0 to 1.0.
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -0,0 +1,254 @@
+//! Heat sources that keep burning in a shape, e.g. along the outline of a logo or a fuse line.
+//!
+//! Emitters are added to a fire with `Doomfire::add_emitter()` and stamp their heat at the end of every `update()`,
+//! regardless of `ignite()` and `extinguish()` which only affect the bottom row.
+//!
+//! # Example
+//! ```
+//! use doomfire::{Doomfire, Emitter};
+//!
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
+//! // A fuse burning from the bottom left towards a bomb in the middle.
+//! doomfire.add_emitter(Emitter::polyline(&[(0.0, 399.0), (150.0, 300.0), (300.0, 200.0)], 3.0, 36));
+//! doomfire.update();
+//! assert_eq!(doomfire.heat()[300 * 600 + 150], 36);
+//! ```
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{Doomfire, PALETTE};
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The number of columns and rows `Emitter::polyline()` rasterizes, enough for a 16K fire.
+pub const MAX_EXTENT: usize = 1 << 14;
+
+/// A shape of pixels that is kept at a fixed heat.
+/// With the `serde` feature it (de)serializes as its `cells` and `heat`, e.g. as part of a `FireConfig`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Shape"))]
+pub struct Emitter {
+    // The pixels of the shape, sorted and without duplicates.
+    cells: Vec<(usize, usize)>,
+    heat: usize,
+}
+
+// What an emitter is deserialized from, so deserialized cells are sorted and clamped like those of `from_cells()`.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Shape {
+    cells: Vec<(usize, usize)>,
+    heat: usize,
+}
+
+#[cfg(feature = "serde")]
+impl From<Shape> for Emitter {
+    fn from(shape: Shape) -> Emitter {
+        Emitter::from_cells(shape.cells, shape.heat)
+    }
+}
+
+impl Emitter {
+    /// Returns an emitter for the given pixels, clamping `heat` to the hottest palette index.
+    /// Pixels outside of the fire are ignored.
+    pub fn from_cells<I: IntoIterator<Item = (usize, usize)>>(cells: I, heat: usize) -> Emitter {
+        let mut cells: Vec<_> = cells.into_iter().collect();
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells.dedup();
+        Emitter {
+            cells,
+            heat: heat.min(PALETTE.len() - 1),
+        }
+    }
+
+    /// Returns an emitter burning along the segments between consecutive `points`, `thickness` pixels wide.
+    /// Close the path by repeating the first point at the end. Only the pixels in the first `MAX_EXTENT`
+    /// columns and rows are kept.
+    /// # Panics
+    /// Panics if a point or the thickness isn't finite.
+    pub fn polyline(points: &[(f32, f32)], thickness: f32, heat: usize) -> Emitter {
+        assert!(
+            thickness.is_finite() && points.iter().all(|p| p.0.is_finite() && p.1.is_finite()),
+            "the points and thickness of a polyline must be finite"
+        );
+        // Anything thinner than a pixel still burns a single pixel wide line.
+        let radius = (thickness / 2.0).max(0.5);
+        let size = (MAX_EXTENT, MAX_EXTENT);
+        let mut cells = Vec::new();
+        if let [point] = points {
+            segment_cells(*point, *point, radius, size, |x, y| cells.push((x, y)));
+        }
+        for pair in points.windows(2) {
+            segment_cells(pair[0], pair[1], radius, size, |x, y| cells.push((x, y)));
+        }
+        Emitter::from_cells(cells, heat)
+    }
+
+    /// Returns an emitter burning in the shape of `text` rendered `px` pixels high with `font`.
+    /// `origin` is the left end of the baseline, pixels covered for at least half by a glyph burn.
+    /// # Examples
+    /// ```no_run
+    /// # use doomfire::{Doomfire, Emitter};
+    /// use fontdue::{Font, FontSettings};
+    ///
+    /// let font = std::fs::read("DejaVuSans-Bold.ttf").unwrap();
+    /// let font = Font::from_bytes(font, FontSettings::default()).unwrap();
+    /// let mut doomfire = Doomfire::new(600, 400);
+    /// doomfire.add_emitter(Emitter::text(&font, "DOOM", 96.0, (100.0, 300.0), 36));
+    /// ```
+    #[cfg(feature = "fontdue")]
+    pub fn text(
+        font: &fontdue::Font,
+        text: &str,
+        px: f32,
+        origin: (f32, f32),
+        heat: usize,
+    ) -> Emitter {
+        let mut cells = Vec::new();
+        let mut pen = origin.0;
+        let mut previous = None;
+        for c in text.chars() {
+            if let Some(kern) = previous.and_then(|p| font.horizontal_kern(p, c, px)) {
+                pen += kern;
+            }
+            let (metrics, coverage) = font.rasterize(c, px);
+            // Bitmap rows go down from the top of the glyph, `ymin` is the bottom of the glyph above the baseline.
+            let left = pen.round() as isize + metrics.xmin as isize;
+            let top = origin.1.round() as isize - metrics.ymin as isize - metrics.height as isize;
+            for (i, &alpha) in coverage.iter().enumerate() {
+                let x = left + (i % metrics.width) as isize;
+                let y = top + (i / metrics.width) as isize;
+                if alpha >= 128 && x >= 0 && y >= 0 {
+                    cells.push((x as usize, y as usize));
+                }
+            }
+            pen += metrics.advance_width;
+            previous = Some(c);
+        }
+        Emitter::from_cells(cells, heat)
+    }
+
+    /// Returns the heat the shape is kept at.
+    pub fn heat(&self) -> usize {
+        self.heat
+    }
+
+    /// Returns the pixels of the shape.
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+}
+
+/// Calls `cell` for the pixels of a `width` x `height` fire whose center lies within `radius` of the segment
+/// from `a` to `b`, row by row. Only the pixels near the segment are visited, none if an input isn't finite.
+pub(crate) fn segment_cells<F: FnMut(usize, usize)>(
+    a: (f32, f32),
+    b: (f32, f32),
+    radius: f32,
+    (width, height): (usize, usize),
+    mut cell: F,
+) {
+    if ![a.0, a.1, b.0, b.1, radius].iter().all(|v| v.is_finite()) || width == 0 || height == 0 {
+        return;
+    }
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length2 = dx * dx + dy * dy;
+    // Float to integer casts saturate, so far off coordinates end up at the edges.
+    let min_y = (a.1.min(b.1) - radius).floor().max(0.0) as usize;
+    let max_y = ((a.1.max(b.1) + radius).ceil().max(0.0) as usize).min(height - 1);
+    // A pixel further than this from the row's part of the segment can't be within `radius`, the extra pixel
+    // makes up for rounding.
+    let reach = radius + 1.0;
+
+    for y in min_y..=max_y {
+        // The part of the segment within `reach` of the row, as fractions of the way from `a` to `b`.
+        let (from, to) = if dy != 0.0 {
+            let (t0, t1) = ((y as f32 - reach - a.1) / dy, (y as f32 + reach - a.1) / dy);
+            (t0.min(t1).max(0.0), t0.max(t1).min(1.0))
+        } else {
+            (0.0, 1.0)
+        };
+        if from > to {
+            continue;
+        }
+        let (x0, x1) = (a.0 + from * dx, a.0 + to * dx);
+        let min_x = (x0.min(x1) - reach).floor().max(0.0) as usize;
+        let max_x = ((x0.max(x1) + reach).ceil().max(0.0) as usize).min(width - 1);
+        for x in min_x..=max_x {
+            let (px, py) = (x as f32 - a.0, y as f32 - a.1);
+            // The closest point on the segment, as a fraction of the way from `a` to `b`.
+            let t = if length2 > 0.0 {
+                ((px * dx + py * dy) / length2).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let (ex, ey) = (px - t * dx, py - t * dy);
+            if ex * ex + ey * ey <= radius * radius {
+                cell(x, y);
+            }
+        }
+    }
+}
+
+impl Doomfire {
+    /// Adds an emitter that keeps burning until `clear_emitters()` is called.
+    pub fn add_emitter(&mut self, emitter: Emitter) {
+        self.emitters.push(emitter);
+    }
+
+    /// Removes all emitters.
+    pub fn clear_emitters(&mut self) {
+        self.emitters.clear();
+    }
+
+    /// Returns the emitters in the order they were added.
+    pub fn emitters(&self) -> &[Emitter] {
+        &self.emitters
+    }
+
+    /// Returns the number of rows and the heat of the ember floor, see `set_embers()`.
+    pub fn embers(&self) -> (usize, usize) {
+        self.embers
+    }
+
+    /// Keeps the bottom `rows` rows at least at `heat`, so an extinguished fire leaves glowing embers instead of going black.
+    /// Pass 0 rows (the default) to remove the embers.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_embers(3, 8);
+    /// doomfire.update();
+    /// assert_eq!(doomfire.heat_at(0, 397), Some(8));
+    /// assert_eq!(doomfire.heat_at(0, 396), Some(0));
+    /// ```
+    pub fn set_embers(&mut self, rows: usize, heat: usize) {
+        self.embers = (rows.min(self.height), heat.min(PALETTE.len() - 1));
+    }
+
+    pub(crate) fn apply_embers(&mut self) {
+        let (rows, heat) = self.embers;
+        let start = (self.height - rows) * self.width;
+        if heat > 0 {
+            self.heated_from(self.height - rows);
+        }
+        for pixel in &mut self.fire_pixels[start..] {
+            *pixel = (*pixel).max(heat as u8);
+        }
+    }
+
+    /// Stamps the heat of all emitters, later emitters win where they overlap.
+    pub(crate) fn apply_emitters(&mut self) {
+        for emitter in &self.emitters {
+            for &(x, y) in &emitter.cells {
+                if x < self.width && y < self.height {
+                    self.fire_pixels[y * self.width + x] = emitter.heat as u8;
+                    self.heat_top = self.heat_top.min(y);
+                }
+            }
+        }
+    }
+}
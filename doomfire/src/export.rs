@@ -0,0 +1,354 @@
+//! Dumping the heat field to CSV or NumPy `.npy` for analysis outside of Rust,
+//! streaming raw frames to files, pipes or sockets for headless rendering,
+//! rendering seamless loops and, with the `gif` feature, recording animated GIFs.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{export::NpySequence, Doomfire};
+//! use std::{fs::File, io::BufWriter};
+//!
+//! let mut doomfire = Doomfire::new(600, 400);
+//! doomfire.ignite();
+//!
+//! // A single frame, loaded in python with `numpy.load("frame.npy")`.
+//! doomfire.write_npy(BufWriter::new(File::create("frame.npy").unwrap())).unwrap();
+//!
+//! // 100 frames in a single (100, 400, 600) array.
+//! let file = BufWriter::new(File::create("frames.npy").unwrap());
+//! let mut sequence = NpySequence::new(file, 600, 400, 100).unwrap();
+//! for _ in 0..100 {
+//!     doomfire.update();
+//!     sequence.push(&doomfire).unwrap();
+//! }
+//! sequence.finish().unwrap();
+//! ```
+use crate::Doomfire;
+#[cfg(feature = "gif")]
+use std::convert::TryFrom;
+use std::io::{self, Write};
+
+/// Writes a version 1.0 `.npy` header for an array of bytes.
+fn write_npy_header<W: Write>(w: &mut W, shape: &str) -> io::Result<()> {
+    let mut header = format!(
+        "{{'descr': '|u1', 'fortran_order': False, 'shape': ({}), }}",
+        shape
+    );
+    // Magic + version + header length + header must be a multiple of 64 bytes, ending with a newline.
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    w.write_all(b"\x93NUMPY\x01\x00")?;
+    w.write_all(&(header.len() as u16).to_le_bytes())?;
+    w.write_all(header.as_bytes())
+}
+
+/// The pixel layout written by `Doomfire::write_frame()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawFormat {
+    /// 4 bytes per pixel, the colors of the current palette.
+    Rgba,
+    /// 1 byte per pixel, the heat (palette index) from 0 to 36.
+    Indexed,
+}
+
+impl Doomfire {
+    /// Streams the current frame as raw pixels, row by row starting at the top left, without allocating.
+    /// Post processing passes are not applied, they need the whole frame, use `draw()` for those.
+    ///
+    /// Piping frames into ffmpeg renders a video without a window:
+    /// `my_app | ffmpeg -f rawvideo -pix_fmt rgba -s 600x400 -i - fire.mp4`
+    /// # Examples
+    /// ```
+    /// # use doomfire::{export::RawFormat, Doomfire};
+    /// let mut doomfire = Doomfire::new(600, 400);
+    /// doomfire.ignite();
+    /// let mut rgba = Vec::new();
+    /// doomfire.write_frame(&mut rgba, RawFormat::Rgba).unwrap();
+    /// assert_eq!(rgba.len(), 600 * 400 * 4);
+    /// assert_eq!(rgba[rgba.len() - 4..], doomfire.palette()[36]);
+    /// ```
+    pub fn write_frame<W: Write>(&self, w: &mut W, format: RawFormat) -> io::Result<()> {
+        match format {
+            RawFormat::Indexed => w.write_all(&self.fire_pixels),
+            RawFormat::Rgba => {
+                let mut buffer = [0; 4096];
+                for chunk in self.fire_pixels.chunks(buffer.len() / 4) {
+                    for (pixel, &h) in buffer.chunks_exact_mut(4).zip(chunk) {
+                        pixel.copy_from_slice(&self.colors[h as usize]);
+                    }
+                    w.write_all(&buffer[..chunk.len() * 4])?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the heat of every pixel as CSV, one line per row with comma separated values.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::new(3, 2);
+    /// doomfire.ignite();
+    /// let mut csv = Vec::new();
+    /// doomfire.write_csv(&mut csv).unwrap();
+    /// assert_eq!(csv, b"0,0,0\n36,36,36\n");
+    /// ```
+    pub fn write_csv<W: Write>(&self, mut w: W) -> io::Result<()> {
+        for row in self.fire_pixels.chunks_exact(self.width) {
+            write_csv_row(&mut w, row)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the heat as a NumPy `.npy` file holding a `(height, width)` array of `uint8`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let doomfire = Doomfire::new(600, 400);
+    /// let mut npy = Vec::new();
+    /// doomfire.write_npy(&mut npy).unwrap();
+    /// assert_eq!(npy.len(), 128 + 600 * 400);
+    /// ```
+    pub fn write_npy<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write_npy_header(&mut w, &format!("{}, {}", self.height, self.width))?;
+        w.write_all(&self.fire_pixels)
+    }
+}
+
+fn write_csv_row<W: Write>(w: &mut W, row: &[u8]) -> io::Result<()> {
+    for (x, heat) in row.iter().enumerate() {
+        if x > 0 {
+            w.write_all(b",")?;
+        }
+        write!(w, "{}", heat)?;
+    }
+    w.write_all(b"\n")
+}
+
+/// Writes consecutive frames into a single `(frames, height, width)` `.npy` array.
+pub struct NpySequence<W: Write> {
+    w: W,
+    len: usize,
+    remaining: usize,
+}
+
+impl<W: Write> NpySequence<W> {
+    /// Writes the header for `frames` frames of a `width` x `height` fire.
+    pub fn new(mut w: W, width: usize, height: usize, frames: usize) -> io::Result<NpySequence<W>> {
+        write_npy_header(&mut w, &format!("{}, {}, {}", frames, height, width))?;
+        Ok(NpySequence {
+            w,
+            len: width * height,
+            remaining: frames,
+        })
+    }
+
+    /// Writes the current frame of `fire`.
+    /// Fails when the size of the fire differs, or all frames announced in `new()` were already written.
+    pub fn push(&mut self, fire: &Doomfire) -> io::Result<()> {
+        if fire.fire_pixels.len() != self.len || self.remaining == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame doesn't fit in the npy array",
+            ));
+        }
+        self.remaining -= 1;
+        self.w.write_all(&fire.fire_pixels)
+    }
+
+    /// Flushes and returns the writer, failing if fewer frames were written than announced.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.remaining > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "fewer frames written than announced",
+            ));
+        }
+        self.w.flush()?;
+        Ok(self.w)
+    }
+}
+
+/// Writes consecutive frames as CSV with a `frame,row,0,1,...` header,
+/// every line starting with the frame and row number followed by the heat of that row.
+/// # Examples
+/// ```
+/// # use doomfire::{export::CsvSequence, Doomfire};
+/// let mut doomfire = Doomfire::new(2, 1);
+/// let mut sequence = CsvSequence::new(Vec::new(), 2).unwrap();
+/// sequence.push(&doomfire).unwrap();
+/// sequence.push(&doomfire).unwrap();
+/// assert_eq!(sequence.into_inner(), b"frame,row,0,1\n0,0,0,0\n1,0,0,0\n");
+/// ```
+pub struct CsvSequence<W: Write> {
+    w: W,
+    frame: usize,
+}
+
+impl<W: Write> CsvSequence<W> {
+    /// Writes the header for a fire of the given width.
+    pub fn new(mut w: W, width: usize) -> io::Result<CsvSequence<W>> {
+        w.write_all(b"frame,row")?;
+        for x in 0..width {
+            write!(w, ",{}", x)?;
+        }
+        w.write_all(b"\n")?;
+        Ok(CsvSequence { w, frame: 0 })
+    }
+
+    /// Writes the current frame of `fire`.
+    pub fn push(&mut self, fire: &Doomfire) -> io::Result<()> {
+        for (y, row) in fire.fire_pixels.chunks_exact(fire.width).enumerate() {
+            write!(self.w, "{},{},", self.frame, y)?;
+            write_csv_row(&mut self.w, row)?;
+        }
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Returns the writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+/// Records consecutive frames as a looping animated GIF.
+/// The heat is written as indices into the palette of the fire, so frames are encoded exactly, without post processing.
+/// # Examples
+/// ```
+/// # use doomfire::{export::GifSequence, Doomfire};
+/// let mut doomfire = Doomfire::new(60, 40);
+/// doomfire.ignite();
+///
+/// let mut sequence = GifSequence::new(Vec::new(), &doomfire, 30).unwrap();
+/// for _ in 0..30 {
+///     doomfire.update();
+///     sequence.push(&doomfire).unwrap();
+/// }
+/// let gif = sequence.finish().unwrap();
+/// assert!(gif.starts_with(b"GIF89a"));
+/// ```
+#[cfg(feature = "gif")]
+pub struct GifSequence<W: Write> {
+    encoder: gif::Encoder<W>,
+    // The rgb colors of the global palette, frames drawn with other colors carry their own.
+    palette: Vec<u8>,
+    width: u16,
+    height: u16,
+    delay: u16,
+    indices: Vec<u8>,
+}
+
+#[cfg(feature = "gif")]
+impl<W: Write> GifSequence<W> {
+    /// Writes the header for frames of the size and palette of `fire`, shown at `fps` frames per second.
+    /// GIF delays are in hundredths of a second and most viewers slow down anything faster than 50 fps.
+    /// Fails if the fire is larger than 65535 pixels in either direction.
+    pub fn new(w: W, fire: &Doomfire, fps: u32) -> io::Result<GifSequence<W>> {
+        let size = |s: usize| {
+            u16::try_from(s).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "fire is too large for a gif")
+            })
+        };
+        let (width, height) = (size(fire.width)?, size(fire.height)?);
+        let palette: Vec<_> = gif_colors(fire).collect();
+        let mut encoder =
+            gif::Encoder::new(w, width, height, &palette).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        Ok(GifSequence {
+            encoder,
+            palette,
+            width,
+            height,
+            delay: (100 / fps.max(1)).max(1) as u16,
+            indices: Vec::with_capacity(fire.fire_pixels.len()),
+        })
+    }
+
+    /// Writes the current frame of `fire`, failing when its size differs from the first one.
+    pub fn push(&mut self, fire: &Doomfire) -> io::Result<()> {
+        if fire.width != self.width as usize || fire.height != self.height as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "frame size differs from the gif",
+            ));
+        }
+        self.indices.clear();
+        self.indices.extend_from_slice(&fire.fire_pixels);
+        let recolored = !gif_colors(fire).eq(self.palette.iter().copied());
+        let frame = gif::Frame {
+            delay: self.delay,
+            width: self.width,
+            height: self.height,
+            palette: if recolored {
+                Some(gif_colors(fire).collect())
+            } else {
+                None
+            },
+            buffer: std::borrow::Cow::Borrowed(&self.indices),
+            ..gif::Frame::default()
+        };
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+
+    /// Writes the end of the GIF and returns the writer.
+    pub fn finish(self) -> io::Result<W> {
+        self.encoder.into_inner()
+    }
+}
+
+/// Returns the rgb channels of the colors `fire` draws with.
+#[cfg(feature = "gif")]
+fn gif_colors(fire: &Doomfire) -> impl Iterator<Item = u8> + '_ {
+    fire.colors.iter().flat_map(|c| [c[0], c[1], c[2]])
+}
+
+impl Doomfire {
+    /// Simulates `frames` frames that loop seamlessly and calls `frame` with the fire showing each of them in turn,
+    /// e.g. to push them to a `GifSequence` or draw them to a video. The fire never returns to an earlier state,
+    /// so this simulates twice as many frames and crossfades the heat of the second half into the first,
+    /// the last frame flowing back into the first. Afterwards the fire continues from the last simulated frame.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::new(60, 40);
+    /// doomfire.ignite();
+    /// // Let the flames grow first, or the loop starts (and ends) with a black sky.
+    /// for _ in 0..40 {
+    ///     doomfire.update();
+    /// }
+    ///
+    /// let mut frame = vec![0; 60 * 40 * 4];
+    /// let mut count = 0;
+    /// doomfire.render_loop(30, |fire| {
+    ///     fire.draw(&mut frame);
+    ///     count += 1;
+    /// });
+    /// assert_eq!(count, 30);
+    /// ```
+    pub fn render_loop<F: FnMut(&Doomfire)>(&mut self, frames: usize, mut frame: F) {
+        let mut states = Vec::with_capacity(frames * 2);
+        for _ in 0..frames * 2 {
+            self.update();
+            states.push(self.fire_pixels.clone());
+        }
+        let (first, second) = states.split_at(frames);
+
+        let last = std::mem::take(&mut self.fire_pixels);
+        for (i, (start, end)) in first.iter().zip(second).enumerate() {
+            // Frame i fades from the second half to the first, so frame 0 is the second half's start
+            // and the last frame is (almost) the first half's end, which it continues from.
+            let t = i as f32 / frames as f32;
+            self.fire_pixels = start
+                .iter()
+                .zip(end)
+                .map(|(&a, &b)| (a as f32 * t + b as f32 * (1.0 - t)).round() as u8)
+                .collect();
+            frame(self);
+        }
+        self.fire_pixels = last;
+    }
+}
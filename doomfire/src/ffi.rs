@@ -0,0 +1,246 @@
+//! A C API for embedding the fire in engines that aren't written in Rust, declared in `include/doomfire.h`.
+//!
+//! Build the library with `cargo rustc --release -p doomfire --features ffi --crate-type cdylib`
+//! (or `staticlib`) and link against it. The header is generated with
+//! `cbindgen --config cbindgen.toml --output include/doomfire.h` in the `doomfire` directory.
+//!
+//! Fires are opaque pointers owned by the library, returned by `doomfire_new()` and freed with `doomfire_free()`.
+//! Every function accepts null for the fire and then does nothing.
+//!
+//! ```c
+//! #include "doomfire.h"
+//!
+//! Doomfire *fire = doomfire_new(320, 168);
+//! uint8_t frame[320 * 168 * 4];
+//! doomfire_ignite(fire);
+//! // Every frame:
+//! doomfire_update(fire);
+//! doomfire_draw(fire, frame, sizeof frame);
+//! // When done:
+//! doomfire_free(fire);
+//! ```
+use crate::{palettes, Doomfire, PALETTE};
+use std::{ffi::CStr, os::raw::c_char, slice};
+
+/// Returns a new (not yet ignited) fire of `width` x `height` pixels, to be freed with `doomfire_free()`.
+#[no_mangle]
+pub extern "C" fn doomfire_new(width: usize, height: usize) -> *mut Doomfire {
+    Box::into_raw(Box::new(Doomfire::new(width, height)))
+}
+
+/// Returns a new fire like `doomfire_new()` that burns the same as every other fire with this seed.
+#[no_mangle]
+pub extern "C" fn doomfire_new_seeded(width: usize, height: usize, seed: u64) -> *mut Doomfire {
+    Box::into_raw(Box::new(Doomfire::from_seed(width, height, seed)))
+}
+
+/// Frees a fire returned by `doomfire_new()`.
+///
+/// # Safety
+/// `fire` must be null or returned by `doomfire_new()`, and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_free(fire: *mut Doomfire) {
+    if !fire.is_null() {
+        drop(Box::from_raw(fire));
+    }
+}
+
+/// Returns the width of the fire in pixels, 0 for null.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_width(fire: *const Doomfire) -> usize {
+    fire.as_ref().map_or(0, Doomfire::width)
+}
+
+/// Returns the height of the fire in pixels, 0 for null.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_height(fire: *const Doomfire) -> usize {
+    fire.as_ref().map_or(0, Doomfire::height)
+}
+
+/// Ignites the fire.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_ignite(fire: *mut Doomfire) {
+    if let Some(fire) = fire.as_mut() {
+        fire.ignite();
+    }
+}
+
+/// Extinguishes the fire.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_extinguish(fire: *mut Doomfire) {
+    if let Some(fire) = fire.as_mut() {
+        fire.extinguish();
+    }
+}
+
+/// Returns whether the fire is lit, false for null.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_is_lit(fire: *const Doomfire) -> bool {
+    fire.as_ref().is_some_and(|fire| fire.is_lit)
+}
+
+/// Updates the fire a single step.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_update(fire: *mut Doomfire) {
+    if let Some(fire) = fire.as_mut() {
+        fire.update();
+    }
+}
+
+/// Draws the fire as rgba to `frame`, a buffer of `len` bytes which must be `width * height * 4`.
+/// Returns false and leaves the frame untouched if it isn't.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`,
+/// `frame` must be null or point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_draw(fire: *const Doomfire, frame: *mut u8, len: usize) -> bool {
+    match fire.as_ref() {
+        Some(fire) if !frame.is_null() && len == fire.width() * fire.height() * 4 => {
+            fire.draw(slice::from_raw_parts_mut(frame, len));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Updates the fire a step and draws it like `doomfire_draw()`, faster than calling both.
+/// The fire isn't updated either if the frame has the wrong size.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`,
+/// `frame` must be null or point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_update_and_draw(
+    fire: *mut Doomfire,
+    frame: *mut u8,
+    len: usize,
+) -> bool {
+    match fire.as_mut() {
+        Some(fire) if !frame.is_null() && len == fire.width() * fire.height() * 4 => {
+            fire.update_and_draw(slice::from_raw_parts_mut(frame, len));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Sets the wind, see `Doomfire::set_wind()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_wind(fire: *mut Doomfire, wind: f32) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_wind(wind);
+    }
+}
+
+/// Sets the intensity, see `Doomfire::set_intensity()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_intensity(fire: *mut Doomfire, intensity: f32) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_intensity(intensity);
+    }
+}
+
+/// Sets the probability of a pixel cooling every step, a negative `probability` restores the default,
+/// see `Doomfire::set_cooling()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_cooling(fire: *mut Doomfire, probability: f32) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_cooling(Some(probability).filter(|&p| p >= 0.0));
+    }
+}
+
+/// Sets the probabilities of drifting sideways and climbing a row, see `Doomfire::set_spread()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_spread(fire: *mut Doomfire, horizontal: f32, vertical: f32) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_spread(horizontal, vertical);
+    }
+}
+
+/// Sets whether heat leaving one side of the fire enters on the other side, see `Doomfire::set_wrap()`.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_wrap(fire: *mut Doomfire, wrap: bool) {
+    if let Some(fire) = fire.as_mut() {
+        fire.set_wrap(wrap);
+    }
+}
+
+/// Sets the palette from 37 rgba colors (148 bytes), from the coldest to the hottest.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`,
+/// `colors` must be null or point to 148 readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_palette(fire: *mut Doomfire, colors: *const u8) {
+    if let (Some(fire), false) = (fire.as_mut(), colors.is_null()) {
+        let mut palette = PALETTE;
+        for (color, rgba) in palette
+            .iter_mut()
+            .zip(slice::from_raw_parts(colors, PALETTE.len() * 4).chunks_exact(4))
+        {
+            color.copy_from_slice(rgba);
+        }
+        fire.set_palette(palette);
+    }
+}
+
+/// Switches to the palette with this name in `palettes`, e.g. `"blue_yellow"`.
+/// Returns false for unknown names.
+///
+/// # Safety
+/// `fire` must be null or a live fire returned by `doomfire_new()`,
+/// `name` must be null or a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn doomfire_set_palette_by_name(
+    fire: *mut Doomfire,
+    name: *const c_char,
+) -> bool {
+    if name.is_null() {
+        return false;
+    }
+    let palette = CStr::from_ptr(name)
+        .to_str()
+        .ok()
+        .and_then(palettes::by_name);
+    match (fire.as_mut(), palette) {
+        (Some(fire), Some(palette)) => {
+            fire.set_palette(palette);
+            true
+        }
+        _ => false,
+    }
+}
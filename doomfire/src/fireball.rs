@@ -0,0 +1,103 @@
+//! Hot blobs flying through a fire, leaving a burning trail, e.g. for projectiles in games.
+//!
+//! # Example
+//! ```
+//! use doomfire::Doomfire;
+//!
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
+//! // Shot from the bottom left corner towards the top right.
+//! doomfire.spawn_fireball((0.0, 399.0), (6.0, -4.0), 8.0);
+//! for _ in 0..10 {
+//!     doomfire.update();
+//! }
+//! assert_eq!(doomfire.fireballs().len(), 1);
+//! ```
+use crate::emitter::segment_cells;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::{Doomfire, PALETTE};
+
+/// The fraction of its radius a fireball keeps every update.
+const DECAY: f32 = 0.96;
+
+/// A fireball spawned with `Doomfire::spawn_fireball()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fireball {
+    position: (f32, f32),
+    velocity: (f32, f32),
+    size: f32,
+    radius: f32,
+}
+
+impl Fireball {
+    /// Returns the center of the fireball.
+    pub fn position(&self) -> (f32, f32) {
+        self.position
+    }
+
+    /// Returns the number of pixels the fireball moves every update.
+    pub fn velocity(&self) -> (f32, f32) {
+        self.velocity
+    }
+
+    /// Returns the current radius of the fireball, which shrinks every update.
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Returns the heat of the fireball, cooling down from white hot as it shrinks.
+    pub fn heat(&self) -> usize {
+        ((PALETTE.len() - 1) as f32 * self.radius / self.size).round() as usize
+    }
+}
+
+impl Doomfire {
+    /// Spawns a fireball of radius `size` at `pos`, moving `velocity` pixels (x right, y down) every update.
+    /// The fireball burns along the way it travels, shrinks and cools down every update,
+    /// and is removed once it's smaller than a pixel or has left the fire.
+    pub fn spawn_fireball(&mut self, pos: (f32, f32), velocity: (f32, f32), size: f32) {
+        if size >= 0.5 {
+            self.fireballs.push(Fireball {
+                position: pos,
+                velocity,
+                size,
+                radius: size,
+            });
+        }
+    }
+
+    /// Returns the fireballs flying through the fire.
+    pub fn fireballs(&self) -> &[Fireball] {
+        &self.fireballs
+    }
+
+    /// Removes all fireballs, the heat they left keeps burning.
+    pub fn clear_fireballs(&mut self) {
+        self.fireballs.clear();
+    }
+
+    /// Moves the fireballs and burns their path into the fire.
+    pub(crate) fn apply_fireballs(&mut self) {
+        let (width, height) = (self.width as f32, self.height as f32);
+        let fire_pixels = &mut self.fire_pixels;
+        let (stride, rows) = (self.width, self.height);
+        let mut top = self.heat_top;
+        self.fireballs.retain_mut(|fireball| {
+            let from = fireball.position;
+            let to = (from.0 + fireball.velocity.0, from.1 + fireball.velocity.1);
+            let heat = fireball.heat() as u8;
+            segment_cells(from, to, fireball.radius, (stride, rows), |x, y| {
+                let pixel = &mut fire_pixels[y * stride + x];
+                *pixel = (*pixel).max(heat);
+                top = top.min(y);
+            });
+
+            fireball.position = to;
+            fireball.radius *= DECAY;
+            let (x, y, r) = (to.0, to.1, fireball.radius);
+            let inside = x + r >= 0.0 && y + r >= 0.0 && x - r < width && y - r < height;
+            inside && r >= 0.5
+        });
+        self.heat_top = top;
+    }
+}
@@ -0,0 +1,305 @@
+//! Pixel formats for `Doomfire::draw_into()`, so frames can be drawn straight into the layout a display,
+//! texture or encoder wants instead of converting rgba afterwards.
+//!
+//! Every format encodes the 37 colors of the palette once per draw, drawing is then a copy per pixel.
+//! Implement `PixelFormat` for formats that aren't here, or pick one of the byte orders in `Format` at runtime
+//! with `Doomfire::draw_with_format()`.
+//!
+//! # Example
+//! ```
+//! use doomfire::{format::Rgb565, Doomfire};
+//!
+//! // A 320x240 SPI display taking 16 bit colors.
+//! let mut doomfire = Doomfire::from_seed(320, 240, 1);
+//! let mut frame = vec![0u16; 320 * 240];
+//! doomfire.ignite();
+//! doomfire.draw_into::<Rgb565>(&mut frame);
+//! // The white hot bottom row.
+//! assert_eq!(frame[239 * 320], 0xFFFF);
+//! ```
+use crate::{Doomfire, PALETTE};
+
+/// A layout of pixels in a buffer of `Channel`s.
+pub trait PixelFormat {
+    /// The elements of the buffer, e.g. `u8` for bytes.
+    type Channel: Copy;
+    /// A single pixel, an array of `Channel`s.
+    type Pixel: Copy + AsRef<[Self::Channel]>;
+
+    /// Returns the pixel of `heat`, drawn in the rgba `color` of the palette.
+    fn encode(color: [u8; 4], heat: usize) -> Self::Pixel;
+}
+
+/// 8 bit red, green, blue and alpha, the format of `Doomfire::draw()`.
+pub struct Rgba8;
+
+impl PixelFormat for Rgba8 {
+    type Channel = u8;
+    type Pixel = [u8; 4];
+
+    fn encode(color: [u8; 4], _: usize) -> [u8; 4] {
+        color
+    }
+}
+
+/// 8 bit blue, green, red and alpha, e.g. for Windows bitmaps and most swapchains.
+pub struct Bgra8;
+
+impl PixelFormat for Bgra8 {
+    type Channel = u8;
+    type Pixel = [u8; 4];
+
+    fn encode([r, g, b, a]: [u8; 4], _: usize) -> [u8; 4] {
+        [b, g, r, a]
+    }
+}
+
+/// 8 bit alpha, red, green and blue, e.g. for Java and Flash style `0xAARRGGBB` pixels stored big endian.
+pub struct Argb8;
+
+impl PixelFormat for Argb8 {
+    type Channel = u8;
+    type Pixel = [u8; 4];
+
+    fn encode([r, g, b, a]: [u8; 4], _: usize) -> [u8; 4] {
+        [a, r, g, b]
+    }
+}
+
+/// 8 bit red, green and blue without alpha, e.g. for video encoders and LED matrices.
+pub struct Rgb8;
+
+impl PixelFormat for Rgb8 {
+    type Channel = u8;
+    type Pixel = [u8; 3];
+
+    fn encode([r, g, b, _]: [u8; 4], _: usize) -> [u8; 3] {
+        [r, g, b]
+    }
+}
+
+/// 5 bits red, 6 bits green and 5 bits blue in a `u16`, the format of most small TFT displays.
+/// Swap the bytes of the frame for displays that expect big endian pixels on a little endian cpu.
+pub struct Rgb565;
+
+impl PixelFormat for Rgb565 {
+    type Channel = u16;
+    type Pixel = [u16; 1];
+
+    fn encode([r, g, b, _]: [u8; 4], _: usize) -> [u16; 1] {
+        [(r as u16 >> 3) << 11 | (g as u16 >> 2) << 5 | b as u16 >> 3]
+    }
+}
+
+/// A `u32` per pixel packing red, green and blue as `0x00RRGGBB`, the format of `minifb` and `softbuffer`.
+pub struct Xrgb32;
+
+impl PixelFormat for Xrgb32 {
+    type Channel = u32;
+    type Pixel = [u32; 1];
+
+    fn encode([r, g, b, _]: [u8; 4], _: usize) -> [u32; 1] {
+        [u32::from_be_bytes([0, r, g, b])]
+    }
+}
+
+/// A `u32` per pixel packing `0xAARRGGBB`.
+pub struct Argb32;
+
+impl PixelFormat for Argb32 {
+    type Channel = u32;
+    type Pixel = [u32; 1];
+
+    fn encode([r, g, b, a]: [u8; 4], _: usize) -> [u32; 1] {
+        [u32::from_be_bytes([a, r, g, b])]
+    }
+}
+
+/// A `u32` per pixel packing `0xAABBGGRR`, the bytes of `Rgba8` read as a little endian `u32`.
+pub struct Abgr32;
+
+impl PixelFormat for Abgr32 {
+    type Channel = u32;
+    type Pixel = [u32; 1];
+
+    fn encode([r, g, b, a]: [u8; 4], _: usize) -> [u32; 1] {
+        [u32::from_be_bytes([a, b, g, r])]
+    }
+}
+
+/// A `u32` per pixel packing `0xRRGGBBAA`.
+pub struct Rgba32;
+
+impl PixelFormat for Rgba32 {
+    type Channel = u32;
+    type Pixel = [u32; 1];
+
+    fn encode([r, g, b, a]: [u8; 4], _: usize) -> [u32; 1] {
+        [u32::from_be_bytes([r, g, b, a])]
+    }
+}
+
+/// The heat itself (0 to 36) as a palette index, for displays and formats with their own color table.
+pub struct Indexed8;
+
+impl PixelFormat for Indexed8 {
+    type Channel = u8;
+    type Pixel = [u8; 1];
+
+    fn encode(_: [u8; 4], heat: usize) -> [u8; 1] {
+        [heat as u8]
+    }
+}
+
+/// The heat stretched from 0 (coldest) to 255 (hottest), ignoring the palette, see `Doomfire::draw_heat()`.
+pub struct Heat8;
+
+impl PixelFormat for Heat8 {
+    type Channel = u8;
+    type Pixel = [u8; 1];
+
+    fn encode(_: [u8; 4], heat: usize) -> [u8; 1] {
+        [(heat * 255 / (PALETTE.len() - 1)) as u8]
+    }
+}
+
+/// The luma of the color (Rec. 601) in 8 bits, for grayscale displays.
+pub struct Gray8;
+
+impl PixelFormat for Gray8 {
+    type Channel = u8;
+    type Pixel = [u8; 1];
+
+    fn encode([r, g, b, _]: [u8; 4], _: usize) -> [u8; 1] {
+        [((r as u32 * 299 + g as u32 * 587 + b as u32 * 114 + 500) / 1000) as u8]
+    }
+}
+
+/// Red, green, blue and alpha as floats from 0.0 to 1.0, still sRGB encoded like the palette.
+/// See `Doomfire::draw_hdr()` for linear light.
+pub struct F32;
+
+impl PixelFormat for F32 {
+    type Channel = f32;
+    type Pixel = [f32; 4];
+
+    fn encode(color: [u8; 4], _: usize) -> [f32; 4] {
+        color.map(|c| c as f32 / 255.0)
+    }
+}
+
+/// The byte orders of `Doomfire::draw_with_format()`, for when the format of a surface is only known at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// See `Rgba8`.
+    Rgba8,
+    /// See `Bgra8`.
+    Bgra8,
+    /// See `Argb8`.
+    Argb8,
+    /// See `Rgb8`.
+    Rgb8,
+}
+
+impl Format {
+    /// Returns the number of bytes of a pixel.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Format::Rgb8 => 3,
+            Format::Rgba8 | Format::Bgra8 | Format::Argb8 => 4,
+        }
+    }
+}
+
+/// The channel orders of `Doomfire::draw_u32()`, most significant byte first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Packed {
+    /// See `Xrgb32`.
+    Xrgb,
+    /// See `Argb32`.
+    Argb,
+    /// See `Abgr32`.
+    Abgr,
+    /// See `Rgba32`.
+    Rgba,
+}
+
+impl Doomfire {
+    /// Draws the fire into `frame` in the pixel format `F`, `frame` holding a pixel per fire pixel, row by row.
+    /// The post processing passes work on rgba bytes and are only run by `draw()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{format::{Bgra8, Indexed8}, Doomfire};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut frame = vec![0; 600 * 400 * 4];
+    /// let mut heat = vec![0; 600 * 400];
+    /// doomfire.ignite();
+    /// doomfire.draw_into::<Bgra8>(&mut frame);
+    /// doomfire.draw_into::<Indexed8>(&mut heat);
+    /// assert_eq!(heat[399 * 600], 36);
+    /// ```
+    pub fn draw_into<F: PixelFormat>(&self, frame: &mut [F::Channel]) {
+        let mut pixels = [F::encode(self.colors[0], 0); PALETTE.len()];
+        for (heat, pixel) in pixels.iter_mut().enumerate() {
+            *pixel = F::encode(self.colors[heat], heat);
+        }
+        let channels = pixels[0].as_ref().len();
+        for (out, &heat) in frame.chunks_exact_mut(channels).zip(&self.fire_pixels) {
+            out.copy_from_slice(pixels[heat as usize].as_ref());
+        }
+    }
+
+    /// Draws the fire into a buffer of a packed `u32` per pixel with the channels in the order of `packed`,
+    /// e.g. `Packed::Xrgb` for the buffers of `minifb` and `softbuffer`. The post processing passes are not run.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{format::Packed, Doomfire};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut buffer = vec![0u32; 600 * 400];
+    /// doomfire.ignite();
+    /// doomfire.draw_u32(&mut buffer, Packed::Xrgb);
+    /// assert_eq!(buffer[0], 0x00070707);
+    /// assert_eq!(buffer[399 * 600], 0x00FFFFFF);
+    /// ```
+    pub fn draw_u32(&self, frame: &mut [u32], packed: Packed) {
+        match packed {
+            Packed::Xrgb => self.draw_into::<Xrgb32>(frame),
+            Packed::Argb => self.draw_into::<Argb32>(frame),
+            Packed::Abgr => self.draw_into::<Abgr32>(frame),
+            Packed::Rgba => self.draw_into::<Rgba32>(frame),
+        }
+    }
+
+    /// Draws the fire like `draw()` in the byte order `format`, `frame` holding `format.bytes_per_pixel()`
+    /// bytes per pixel. The post processing passes are run for the formats with alpha,
+    /// on rgba before it's reordered. Use `draw_into()` when the format is known at compile time.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{format::Format, Doomfire};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut frame = vec![0; 600 * 400 * Format::Bgra8.bytes_per_pixel()];
+    /// doomfire.ignite();
+    /// doomfire.draw_with_format(&mut frame, Format::Bgra8);
+    /// ```
+    pub fn draw_with_format(&self, frame: &mut [u8], format: Format) {
+        if format == Format::Rgba8 {
+            return self.draw(frame);
+        }
+        if format == Format::Rgb8 || self.postfx.is_empty() {
+            return match format {
+                Format::Rgba8 => self.draw_into::<Rgba8>(frame),
+                Format::Bgra8 => self.draw_into::<Bgra8>(frame),
+                Format::Argb8 => self.draw_into::<Argb8>(frame),
+                Format::Rgb8 => self.draw_into::<Rgb8>(frame),
+            };
+        }
+        self.draw(frame);
+        for pixel in frame.chunks_exact_mut(4) {
+            if format == Format::Bgra8 {
+                pixel.swap(0, 2);
+            } else {
+                pixel.rotate_right(1);
+            }
+        }
+    }
+}
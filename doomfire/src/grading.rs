@@ -0,0 +1,77 @@
+//! Adjusting the drawn colors of a fire to match the lighting of a scene, without editing the palette.
+//!
+//! The adjusted palette is computed once when a setting changes, so changing them every frame costs nothing while drawing.
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Doomfire;
+
+impl Doomfire {
+    /// Returns the color temperature shift, see `set_temperature()`.
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    /// Shifts the colors of the palette towards blue (-1.0) or orange (1.0), 0.0 (the default) keeps them unchanged.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // Warm at noon and cool at midnight.
+    /// let hour = 22.0_f32;
+    /// doomfire.set_temperature(-(hour / 24.0 * std::f32::consts::TAU).cos());
+    /// assert!(doomfire.temperature() < 0.0);
+    /// ```
+    pub fn set_temperature(&mut self, temperature: f32) {
+        self.temperature = temperature.clamp(-1.0, 1.0);
+        self.shift_colors();
+    }
+
+    /// Returns the brightness, contrast and gamma of the drawn colors, see `set_adjustments()`.
+    pub fn adjustments(&self) -> (f32, f32, f32) {
+        self.adjustments
+    }
+
+    /// Adjusts the exposure of the drawn colors, with every channel going from 0.0 to 1.0:
+    /// `gamma` brightens (above 1.0) or darkens (below 1.0) the midtones,
+    /// `contrast` scales the distance from 0.5 and `brightness` is added last.
+    /// The defaults are a brightness of 0.0, a contrast of 1.0 and a gamma of 1.0, which keep the palette unchanged.
+    /// The alpha channel is never adjusted.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// // A dimmer fire for a dark scene.
+    /// doomfire.set_adjustments(-0.1, 0.9, 0.8);
+    /// doomfire.ignite();
+    ///
+    /// let mut frame = vec![0; 600 * 400 * 4];
+    /// doomfire.draw(&mut frame);
+    /// // The white hot bottom row is drawn light gray.
+    /// assert_eq!(frame[399 * 600 * 4], 217);
+    /// ```
+    pub fn set_adjustments(&mut self, brightness: f32, contrast: f32, gamma: f32) {
+        self.adjustments = (brightness, contrast.max(0.0), gamma.max(0.01));
+        self.shift_colors();
+    }
+
+    /// Recomputes the colors looked up when drawing from the palette, the temperature and the adjustments.
+    pub(crate) fn shift_colors(&mut self) {
+        self.drawn_top = None;
+        let t = self.temperature;
+        // Warm tones dim the blue most and the green a bit, cool tones do the same to red and green.
+        let gains = if t >= 0.0 {
+            [1.0, 1.0 - 0.2 * t, 1.0 - 0.6 * t]
+        } else {
+            [1.0 + 0.6 * t, 1.0 + 0.2 * t, 1.0]
+        };
+        let (brightness, contrast, gamma) = self.adjustments;
+        for (dst, src) in self.colors.iter_mut().zip(self.palette.iter()) {
+            for c in 0..3 {
+                let v = (src[c] as f32 / 255.0).powf(1.0 / gamma);
+                let v = (v - 0.5) * contrast + 0.5 + brightness;
+                dst[c] = (v.clamp(0.0, 1.0) * gains[c] * 255.0).round() as u8;
+            }
+            dst[3] = src[3];
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Editing the heat of a fire directly.
+use crate::{emitter::segment_cells, Doomfire, PALETTE};
+
+/// A rectangle of pixels, `x` and `y` being the top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    /// The column of the left edge.
+    pub x: usize,
+    /// The row of the top edge.
+    pub y: usize,
+    /// The width in pixels.
+    pub width: usize,
+    /// The height in pixels.
+    pub height: usize,
+}
+
+impl Rect {
+    /// Returns a rectangle with its top left corner at `x`, `y`.
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl Doomfire {
+    /// Returns a (not yet ignited) fire starting from `heat`, the heat of every pixel row by row starting at the top left.
+    /// Values above the hottest palette index are clamped, returns `None` if there are not `width * height` values.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // A hot spot in the middle of a 3 x 3 fire.
+    /// let doomfire = Doomfire::from_heat_map(3, 3, &[0, 0, 0, 0, 36, 0, 0, 0, 0]).unwrap();
+    /// assert_eq!(doomfire.heat_at(1, 1), Some(36));
+    /// assert!(Doomfire::from_heat_map(3, 3, &[0; 8]).is_none());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_heat_map(width: usize, height: usize, heat: &[u8]) -> Option<Doomfire> {
+        if heat.len() != width * height {
+            return None;
+        }
+        let mut fire = Doomfire::new(width, height);
+        for (pixel, &h) in fire.fire_pixels.iter_mut().zip(heat) {
+            *pixel = h.min(PALETTE.len() as u8 - 1);
+        }
+        fire.heated_from(0);
+        Some(fire)
+    }
+
+    /// Returns the heat of the pixel at `x`, `y`, or `None` outside of the fire.
+    pub fn heat_at(&self, x: usize, y: usize) -> Option<u8> {
+        if x < self.width && y < self.height {
+            Some(self.fire_pixels[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the heat of the pixel at `x`, `y`, clamped to the hottest palette index.
+    /// Pixels outside of the fire are ignored.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_heat(10, 20, 100);
+    /// assert_eq!(doomfire.heat_at(10, 20), Some(36));
+    /// ```
+    pub fn set_heat(&mut self, x: usize, y: usize, heat: usize) {
+        if x < self.width && y < self.height {
+            self.fire_pixels[y * self.width + x] = heat.min(PALETTE.len() - 1) as u8;
+            self.heated_from(y);
+        }
+    }
+
+    /// Sets the heat along a `thickness` pixels wide line from `from` to `to`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.heat_line((100.0, 300.0), (500.0, 300.0), 2.0, 30);
+    /// assert_eq!(doomfire.heat_at(300, 300), Some(30));
+    /// ```
+    pub fn heat_line(&mut self, from: (f32, f32), to: (f32, f32), thickness: f32, heat: usize) {
+        let size = (self.width, self.height);
+        segment_cells(from, to, (thickness / 2.0).max(0.5), size, |x, y| {
+            self.set_heat(x, y, heat)
+        });
+    }
+
+    /// Sets the heat of every pixel in `rect`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Doomfire, Rect};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.heat_rect(Rect::new(250, 350, 100, 50), 36);
+    /// assert_eq!(doomfire.heat_at(349, 399), Some(36));
+    /// assert_eq!(doomfire.heat_at(350, 399), Some(0));
+    /// ```
+    pub fn heat_rect(&mut self, rect: Rect, heat: usize) {
+        let heat = heat.min(PALETTE.len() - 1) as u8;
+        let x_end = (rect.x + rect.width).min(self.width);
+        let y_end = (rect.y + rect.height).min(self.height);
+        self.heated_from(rect.y);
+        for y in rect.y..y_end {
+            for pixel in &mut self.fire_pixels[y * self.width..][rect.x.min(x_end)..x_end] {
+                *pixel = heat;
+            }
+        }
+    }
+
+    /// Sets the heat of every pixel within `radius` of `center`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.heat_circle((300.0, 200.0), 20.0, 36);
+    /// assert_eq!(doomfire.heat_at(300, 219), Some(36));
+    /// assert_eq!(doomfire.heat_at(300, 221), Some(0));
+    /// ```
+    pub fn heat_circle(&mut self, center: (f32, f32), radius: f32, heat: usize) {
+        let size = (self.width, self.height);
+        segment_cells(center, center, radius, size, |x, y| {
+            self.set_heat(x, y, heat)
+        });
+    }
+
+    /// Copies the heat in `src` of `other` to this fire, with the top left corner at `dst`.
+    /// Parts of the region that fall outside of either fire are skipped.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Doomfire, Rect};
+    /// let mut scene = Doomfire::from_seed(600, 400, 1);
+    /// scene.ignite();
+    /// for _ in 0..100 {
+    ///     scene.update();
+    /// }
+    ///
+    /// // A close-up of the bottom left corner, seeded with the heat of the scene.
+    /// let mut close_up = Doomfire::from_seed(100, 50, 1);
+    /// close_up.copy_region_from(&scene, Rect::new(0, 350, 100, 50), (0, 0));
+    /// assert_eq!(close_up.heat()[49 * 100], 36);
+    /// ```
+    pub fn copy_region_from(&mut self, other: &Doomfire, src: Rect, dst: (usize, usize)) {
+        let (dst_x, dst_y) = dst;
+        if src.x >= other.width || dst_x >= self.width {
+            return;
+        }
+        let width = src.width.min(other.width - src.x).min(self.width - dst_x);
+        let height = src
+            .height
+            .min(other.height.saturating_sub(src.y))
+            .min(self.height.saturating_sub(dst_y));
+        self.heated_from(dst_y);
+
+        for row in 0..height {
+            let from = (src.y + row) * other.width + src.x;
+            let to = (dst_y + row) * self.width + dst_x;
+            self.fire_pixels[to..to + width]
+                .copy_from_slice(&other.fire_pixels[from..from + width]);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Callbacks run when a fire updates or changes state, so applications don't have to poll for it.
+use crate::Doomfire;
+use alloc::{boxed::Box, vec::Vec};
+use core::mem;
+
+type Hook = Box<dyn FnMut(&Doomfire) + Send>;
+
+/// The callbacks registered on a fire.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    update: Vec<Hook>,
+    ignite: Vec<Hook>,
+    extinguished: Vec<Hook>,
+    // Whether the extinguished hooks already ran since the last `ignite()`.
+    went_out: bool,
+}
+
+impl Hooks {
+    /// Returns the number of bytes taken by the callbacks and what they captured.
+    pub(crate) fn memory_usage(&self) -> usize {
+        [&self.update, &self.ignite, &self.extinguished]
+            .iter()
+            .map(|hooks| {
+                hooks.capacity() * core::mem::size_of::<Hook>()
+                    + hooks
+                        .iter()
+                        .map(|hook| core::mem::size_of_val(&**hook))
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+}
+
+impl Doomfire {
+    /// Registers a callback that runs at the end of every `update()` (and `update_and_draw()`).
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    ///
+    /// let frames = Arc::new(AtomicUsize::new(0));
+    /// let counter = frames.clone();
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.on_update(move |_| {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// doomfire.update();
+    /// doomfire.update();
+    /// assert_eq!(frames.load(Ordering::Relaxed), 2);
+    /// ```
+    pub fn on_update<F: FnMut(&Doomfire) + Send + 'static>(&mut self, hook: F) {
+        self.hooks.update.push(Box::new(hook));
+    }
+
+    /// Registers a callback that runs every time `ignite()` is called.
+    pub fn on_ignite<F: FnMut(&Doomfire) + Send + 'static>(&mut self, hook: F) {
+        self.hooks.ignite.push(Box::new(hook));
+    }
+
+    /// Registers a callback that runs once the fire has completely died out after `extinguish()`,
+    /// which is when every pixel above the ember floor (see `set_embers()`) has gone black.
+    /// Emitters keep the fire burning, so this doesn't run while there are any.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    ///
+    /// let out = Arc::new(AtomicBool::new(false));
+    /// let flag = out.clone();
+    /// let mut doomfire = Doomfire::from_seed(60, 40, 1);
+    /// doomfire.on_extinguished(move |_| flag.store(true, Ordering::Relaxed));
+    /// doomfire.ignite();
+    /// doomfire.update();
+    /// doomfire.extinguish();
+    /// while !out.load(Ordering::Relaxed) {
+    ///     doomfire.update();
+    /// }
+    /// assert!(doomfire.heat().iter().all(|&h| h == 0));
+    /// ```
+    pub fn on_extinguished<F: FnMut(&Doomfire) + Send + 'static>(&mut self, hook: F) {
+        self.hooks.extinguished.push(Box::new(hook));
+    }
+
+    /// Removes all callbacks registered with `on_update()`, `on_ignite()` and `on_extinguished()`.
+    pub fn clear_hooks(&mut self) {
+        self.hooks = Hooks::default();
+    }
+
+    /// Returns whether `run_update_hooks()` may run any callbacks.
+    pub(crate) fn has_update_hooks(&self) -> bool {
+        !self.hooks.update.is_empty()
+            || !self.is_lit && !self.hooks.went_out && !self.hooks.extinguished.is_empty()
+    }
+
+    /// Runs the update hooks, followed by the extinguished hooks if the fire just died out.
+    pub(crate) fn run_update_hooks(&mut self) {
+        if !self.hooks.update.is_empty() {
+            let mut hooks = mem::take(&mut self.hooks.update);
+            for hook in &mut hooks {
+                hook(self);
+            }
+            self.hooks.update = hooks;
+        }
+
+        if !self.is_lit && !self.hooks.went_out && !self.hooks.extinguished.is_empty() {
+            let burning = (self.height - self.embers.0) * self.width;
+            // The bottom rows are the last to go out, so look there first.
+            if self.fire_pixels[..burning].iter().rev().all(|&h| h == 0) {
+                self.hooks.went_out = true;
+                let mut hooks = mem::take(&mut self.hooks.extinguished);
+                for hook in &mut hooks {
+                    hook(self);
+                }
+                self.hooks.extinguished = hooks;
+            }
+        }
+    }
+
+    /// Runs the ignite hooks.
+    pub(crate) fn run_ignite_hooks(&mut self) {
+        self.hooks.went_out = false;
+        if !self.hooks.ignite.is_empty() {
+            let mut hooks = mem::take(&mut self.hooks.ignite);
+            for hook in &mut hooks {
+                hook(self);
+            }
+            self.hooks.ignite = hooks;
+        }
+    }
+}
@@ -0,0 +1,659 @@
+//! The propagation of the fire as free functions on heat buffers owned by the caller,
+//! e.g. in shared memory, GPU staging buffers or memory mapped files.
+//!
+//! `Doomfire::update()` runs the same code on its own buffer, before and after its effects (rain, fireballs,
+//! emitters, ...), which are not part of the kernel.
+//!
+//! # Example
+//! ```
+//! use doomfire::kernel::{self, Params};
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let (width, height) = (320, 200);
+//! let mut heat = vec![0u8; width * height];
+//! // Ignite by setting the bottom row white hot.
+//! for pixel in &mut heat[(height - 1) * width..] {
+//!     *pixel = 36;
+//! }
+//! let mut rng = StdRng::seed_from_u64(1);
+//! for _ in 0..100 {
+//!     kernel::step(&mut heat, width, height, &Params::default(), &mut rng);
+//! }
+//! assert!(heat[(height - 2) * width..(height - 1) * width].iter().any(|&h| h > 0));
+//! ```
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Distribution;
+use alloc::vec;
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rand::SeedableRng;
+#[cfg(feature = "rayon")]
+use rand_pcg::Pcg64Mcg;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// A type that holds the heat of a pixel, from 0 to 36.
+pub trait Heat: Copy {
+    /// Returns the heat as a palette index.
+    fn heat(self) -> usize;
+    /// Returns the heat `heat`, which is at most 36.
+    fn from_heat(heat: usize) -> Self;
+}
+
+impl Heat for u8 {
+    fn heat(self) -> usize {
+        self as usize
+    }
+
+    fn from_heat(heat: usize) -> Self {
+        heat as u8
+    }
+}
+
+impl Heat for u16 {
+    fn heat(self) -> usize {
+        self as usize
+    }
+
+    fn from_heat(heat: usize) -> Self {
+        heat as u16
+    }
+}
+
+impl Heat for usize {
+    fn heat(self) -> usize {
+        self
+    }
+
+    fn from_heat(heat: usize) -> Self {
+        heat
+    }
+}
+
+/// The settings of a step, the same as the `Doomfire` setters of the same names.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Params<'a> {
+    /// Whether the fire is lit, an extinguished fire dies out. See `Doomfire::ignite()`.
+    pub lit: bool,
+    /// See `Doomfire::set_wrap()`.
+    pub wrap: bool,
+    /// See `Doomfire::set_distribution()`.
+    pub distribution: Distribution,
+    /// The horizontal and vertical spread, see `Doomfire::set_spread()`.
+    pub spread: (f32, f32),
+    /// See `Doomfire::set_wind()`.
+    pub wind: f32,
+    /// See `Doomfire::set_wind_map()`, empty for none.
+    pub wind_map: &'a [i8],
+    /// See `Doomfire::set_cooling()`.
+    pub cooling: Option<f32>,
+    /// See `Doomfire::set_cooling_curve()`, an empty curve is the same as `None`.
+    pub cooling_curve: Option<&'a [f32]>,
+}
+
+/// The settings of `Doomfire::new()` for a lit fire.
+impl Default for Params<'_> {
+    fn default() -> Self {
+        Params {
+            lit: true,
+            wrap: false,
+            distribution: Distribution::Classic,
+            spread: (1.0, 1.0),
+            wind: 0.0,
+            wind_map: &[],
+            cooling: None,
+            cooling_curve: None,
+        }
+    }
+}
+
+impl<'a> Params<'a> {
+    /// Returns these settings leaning the flames away from `gravity`, e.g. the reading of an accelerometer, in the
+    /// coordinates of the fire (x to the right, y down) and of any length.
+    ///
+    /// Up to 45° off vertical the flames are bent over by extra `wind`, 1.0 at 45°.
+    /// Further over they also climb less often: the heat creeps along the rows when up is sideways
+    /// and doesn't climb at all once the screen is upside down. Without gravity (free fall) nothing changes.
+    /// # Examples
+    /// ```
+    /// use doomfire::kernel::Params;
+    ///
+    /// // Upright.
+    /// assert_eq!(Params::default().tilted((0.0, 9.8)), Params::default());
+    /// // Lying on its right side, up is to the left.
+    /// let params = Params::default().tilted((9.8, 0.0));
+    /// assert_eq!((params.wind, params.spread.1), (-1.0, 0.0));
+    /// ```
+    pub fn tilted(mut self, gravity: (f32, f32)) -> Params<'a> {
+        let length = gravity.0.hypot(gravity.1);
+        if length <= f32::EPSILON {
+            return self;
+        }
+        // The real world's up, as its sideways and upward part.
+        let (side, up) = (-gravity.0 / length, gravity.1 / length);
+        // One column per row at most, climbing less often for steeper angles.
+        let steps = side.abs().max(up).max(f32::EPSILON);
+        self.wind = (self.wind + side / steps).clamp(-1.0, 1.0);
+        self.spread.1 *= (up / steps).clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// The number of rows above its own that heat can climb to in a single step.
+const REACH: usize = 3;
+
+/// The number of rows of scratch space `step_rows()` needs.
+pub(crate) const SCRATCH_ROWS: usize = 2 * (REACH + 1);
+
+/// Moves the heat of a `width` x `height` fire up a single step, `heat` holding the pixels row by row from the top left.
+///
+/// The rows are stepped from the bottom up, walking memory in order, and burn like stepping every column with
+/// `step_column()` from left to right. That moves heat drifting to a column on its right up again in the same step,
+/// so where heat lands is kept track of in a scratch buffer of 8 rows, which this allocates.
+/// `Doomfire::update()` reuses its own.
+/// # Examples
+/// ```
+/// use doomfire::kernel::{self, Params};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // The average heat of a 160 x 100 fire over 200 steps, after it grew to its usual height.
+/// fn average(params: &Params, by_rows: bool) -> f64 {
+///     let (width, height) = (160, 100);
+///     let mut heat = vec![0u8; width * height];
+///     for pixel in &mut heat[(height - 1) * width..] {
+///         *pixel = 36;
+///     }
+///     let mut rng = StdRng::seed_from_u64(1);
+///     let mut sum = 0;
+///     for i in 0..300 {
+///         if by_rows {
+///             kernel::step(&mut heat, width, height, params, &mut rng);
+///         } else {
+///             for x in 0..width {
+///                 kernel::step_column(&mut heat, width, height, x, params, &mut rng);
+///             }
+///         }
+///         if i >= 100 {
+///             sum += heat.iter().map(|&h| h as usize).sum::<usize>();
+///         }
+///     }
+///     sum as f64 / (200 * width * height) as f64
+/// }
+///
+/// let curve = [1.5, 1.0, 0.5];
+/// for params in &[
+///     Params::default(),
+///     Params { wind: 0.6, wrap: true, ..Params::default() },
+///     Params { spread: (0.5, 0.7), ..Params::default() },
+///     Params { lit: false, cooling_curve: Some(&curve), ..Params::default() },
+/// ] {
+///     let (rows, columns) = (average(params, true), average(params, false));
+///     assert!((rows / columns - 1.0).abs() < 0.05, "{:?}: {} instead of {}", params, rows, columns);
+/// }
+/// ```
+pub fn step<H: Heat, R: Rng>(
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    params: &Params,
+    rng: &mut R,
+) {
+    let mut scratch = vec![0; SCRATCH_ROWS * width];
+    step_rows(heat, width, height, params, rng, &mut scratch, 0, |_, _| ());
+}
+
+/// Returns the first row from `from` down that has heat, `height` if none.
+pub(crate) fn heat_top<H: Heat>(heat: &[H], width: usize, height: usize, from: usize) -> usize {
+    (from..height)
+        .find(|&y| {
+            heat[y * width..(y + 1) * width]
+                .iter()
+                .any(|pixel| pixel.heat() > 0)
+        })
+        .unwrap_or(height)
+}
+
+/// `step()` with a scratch buffer of at least `SCRATCH_ROWS * width`, calling `finished` with every row (and `heat`)
+/// as soon as no more heat moves into it, from the bottom up. The rows above `cold` are black, so once no heat
+/// climbs into them they're left as they are. Returns the highest row given heat, `height` if none.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn step_rows<H: Heat, R: Rng>(
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    params: &Params,
+    rng: &mut R,
+    scratch: &mut [u8],
+    cold: usize,
+    mut finished: impl FnMut(&mut [H], usize),
+) -> usize {
+    let mut top = height;
+    if height == 0 {
+        return top;
+    }
+    // For the row being stepped and the rows its heat can climb to, the heat their pixels read,
+    // copied from `heat` before anything lands in the row and updated where heat climbs again,
+    // and which column the heat landing in them came from, see `order()`:
+    // the last one to land in a pixel (the lower 3 bits) and the last one that climbs again (the upper ones).
+    let (reads, landed) = scratch[..SCRATCH_ROWS * width].split_at_mut((REACH + 1) * width);
+    let slot = |y: usize| y % (REACH + 1) * width;
+    // Whether all heat climbs a single row, which needs no bookkeeping of where it landed.
+    let climbs = params.lit && params.spread.1 >= 1.0;
+    let load = |reads: &mut [u8], landed: &mut [u8], heat: &[H], y: usize| {
+        let row = &heat[y * width..(y + 1) * width];
+        for (read, pixel) in reads[slot(y)..slot(y) + width].iter_mut().zip(row) {
+            *read = pixel.heat() as u8;
+        }
+        landed[slot(y)..slot(y) + width].fill(0);
+    };
+    for y in height.saturating_sub(REACH)..height {
+        load(reads, landed, heat, y);
+    }
+    // The rows from 0 to here are black and stay black.
+    let mut black = 0;
+    for y in (1..height).rev() {
+        if y < cold.min(top) {
+            black = y;
+            break;
+        }
+        if y >= REACH {
+            load(reads, landed, heat, y - REACH);
+        }
+        if climbs {
+            // Everything landing in the row above comes from this row, from left to right like the columns.
+            let (row, above) = (slot(y), slot(y - 1));
+            let (low, high) = reads.split_at_mut(row.max(above));
+            let (src, climbing) = if row > above {
+                (&high[..width], &mut low[above..above + width])
+            } else {
+                (&low[row..row + width], &mut high[..width])
+            };
+            let dst = &mut heat[(y - 1) * width..y * width];
+            if climb_row(src, climbing, dst, height, y, params, rng) {
+                top = y - 1;
+            }
+            finished(heat, y);
+            continue;
+        }
+        for x in 0..width {
+            let src_pixel = reads[slot(y) + x] as usize;
+            let (dst_x, dst_y, dst_heat) = step_pixel(src_pixel, width, height, x, y, params, rng);
+            let i = slot(dst_y) + dst_x;
+            let from = order(x, dst_x, width);
+            // Heat from columns further right lands later when stepping by column, and from further down
+            // in the same column too, which is stepped first here.
+            if from > landed[i] & 7 {
+                if dst_heat > 0 {
+                    top = top.min(dst_y);
+                }
+                heat[dst_y * width + dst_x] = H::from_heat(dst_heat);
+                landed[i] = landed[i] & !7 | from;
+            }
+            // The columns to the right are stepped after this one when stepping by column, see above.
+            if dst_x > x && from > landed[i] >> 3 {
+                reads[i] = dst_heat as u8;
+                landed[i] = landed[i] & 7 | from << 3;
+            }
+        }
+        finished(heat, y);
+    }
+    for y in (0..=black).rev() {
+        finished(heat, y);
+    }
+    top
+}
+
+/// Moves the heat `src` of row `y` up into `dst`, the row above, when all heat climbs a single row.
+/// Heat drifting a column to the right climbs again, so it's written to `climbing` as well.
+/// Returns whether any heat landed. Kept out of line, which runs faster than inlined into `step_rows()`.
+#[inline(never)]
+fn climb_row<H: Heat, R: Rng>(
+    src: &[u8],
+    climbing: &mut [u8],
+    dst: &mut [H],
+    height: usize,
+    y: usize,
+    params: &Params,
+    rng: &mut R,
+) -> bool {
+    let width = src.len();
+    let mut hot = false;
+    for (x, &src_pixel) in src.iter().enumerate() {
+        let (dst_x, _, dst_heat) = step_pixel(src_pixel as usize, width, height, x, y, params, rng);
+        hot |= dst_heat > 0;
+        dst[dst_x] = H::from_heat(dst_heat);
+        if dst_x > x {
+            climbing[dst_x] = dst_heat as u8;
+        }
+    }
+    hot
+}
+
+/// Returns when column `x` is stepped relative to the other columns whose heat can land in column `dst_x`,
+/// from 1 (first) to 7 (last). Heat wrapping around the left edge comes from the first columns,
+/// around the right edge from the last one.
+#[inline(always)]
+fn order(x: usize, dst_x: usize, width: usize) -> u8 {
+    match x as isize - dst_x as isize {
+        offset if offset > 2 => 7,
+        offset if offset < -1 => (offset + width as isize) as u8,
+        offset => (offset + 4) as u8,
+    }
+}
+
+/// Moves the heat of column `x` up, heat lands at most 2 columns to the left and 1 to the right.
+/// Stepping every column from left to right burns like `step()`.
+/// Returns the highest row the column gave heat to, `height` if none.
+pub fn step_column<H: Heat, R: Rng>(
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    x: usize,
+    params: &Params,
+    rng: &mut R,
+) -> usize {
+    step_cells(heat, width, height, x, params, rng)
+}
+
+/// Marks the pixels of `step_buffered()` no heat landed in yet, a heat no pixel has.
+const UNTOUCHED: usize = u8::MAX as usize;
+
+/// Like `step()`, but reads the heat of every pixel from `previous` and writes the fire after the step to `heat`,
+/// so no pixel reads heat that already moved this step. Where the heat of several pixels lands in the same one
+/// the heat from furthest down wins, and of those the heat from furthest right. Pixels no heat lands in keep their
+/// previous heat. So the fire depends only on the random draws, not on the order the pixels are stepped in.
+/// Returns the highest row given heat, `height` if none.
+/// # Examples
+/// ```
+/// use doomfire::kernel::{self, Params};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let (width, height) = (8, 4);
+/// let mut previous = vec![0u8; width * height];
+/// for pixel in &mut previous[3 * width..] {
+///     *pixel = 36;
+/// }
+/// let mut heat = vec![0u8; width * height];
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let top = kernel::step_buffered(&previous, &mut heat, width, height, &Params::default(), &mut rng);
+/// // The bottom row climbed a single row and is still burning.
+/// assert_eq!(top, 2);
+/// assert_eq!(heat[3 * width..], previous[3 * width..]);
+/// ```
+pub fn step_buffered<H: Heat, R: Rng>(
+    previous: &[H],
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    params: &Params,
+    rng: &mut R,
+) -> usize {
+    let mut top = height;
+    let heat = &mut heat[..width * height];
+    heat.iter_mut()
+        .for_each(|pixel| *pixel = H::from_heat(UNTOUCHED));
+    for y in 1..height {
+        for x in 0..width {
+            let src_pixel = previous[y * width + x].heat();
+            let (dst_x, dst_y, dst_heat) = step_pixel(src_pixel, width, height, x, y, params, rng);
+            // Stepping from the top left, the heat from furthest down, then furthest right lands last.
+            heat[dst_y * width + dst_x] = H::from_heat(dst_heat);
+            if dst_heat > 0 {
+                top = top.min(dst_y);
+            }
+        }
+    }
+    for (pixel, &old) in heat.iter_mut().zip(previous) {
+        if pixel.heat() == UNTOUCHED {
+            *pixel = old;
+        }
+    }
+    top
+}
+
+/// The pixels a column is stepped in, a slice or the strips of `step_parallel()`.
+trait Cells<H> {
+    fn get(&self, i: usize) -> H;
+    fn set(&mut self, i: usize, heat: H);
+}
+
+impl<H: Copy> Cells<H> for [H] {
+    #[inline(always)]
+    fn get(&self, i: usize) -> H {
+        self[i]
+    }
+
+    #[inline(always)]
+    fn set(&mut self, i: usize, heat: H) {
+        self[i] = heat;
+    }
+}
+
+/// `step_column()` on any `Cells`.
+#[inline(always)]
+fn step_cells<H: Heat, C: Cells<H> + ?Sized, R: Rng>(
+    heat: &mut C,
+    width: usize,
+    height: usize,
+    x: usize,
+    params: &Params,
+    rng: &mut R,
+) -> usize {
+    let mut top = height;
+    for y in 1..height {
+        let src_pixel = heat.get(y * width + x).heat();
+        let (dst_x, dst_y, dst_heat) = step_pixel(src_pixel, width, height, x, y, params, rng);
+        if dst_heat > 0 {
+            top = top.min(dst_y);
+        }
+        heat.set(dst_y * width + dst_x, H::from_heat(dst_heat));
+    }
+    top
+}
+
+/// Returns where the heat `src_pixel` of the pixel at `(x, y)` lands and how hot it is when it does.
+#[inline(always)]
+fn step_pixel<R: Rng>(
+    src_pixel: usize,
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    params: &Params,
+    rng: &mut R,
+) -> (usize, usize, usize) {
+    // Don't decrease if already 0, otherwise negative overflow.
+    if src_pixel == 0 {
+        return (x, y - 1, 0);
+    }
+    let rand = params.distribution.sample(rng);
+    let drift = blow(params, width, height, x, y, rand, rng);
+    let (horizontal, vertical) = params.spread;
+    // give dst a random change to go left/right
+    let dst_x = if horizontal >= 1.0 || rng.gen::<f32>() < horizontal {
+        offset_x(params.wrap, width, x, 1 - drift as isize)
+    } else {
+        x
+    };
+    // When is_lit: use infite algorithm, when !is_lit: use algorithm that dies out.
+    let dst_y = if vertical < 1.0 && rng.gen::<f32>() >= vertical {
+        // Not climbing, the heat stays in its row.
+        y
+    } else if params.lit {
+        y - 1
+    } else {
+        // not sure why but this if branch cuts performance in half??
+        let rand2 = params.distribution.sample(rng);
+        // Saturating so no overflow is possible.
+        y.saturating_sub(rand2)
+    };
+    let cooling = cooling_at(params, height, y, rand, rng);
+    (dst_x, dst_y, src_pixel.saturating_sub(cooling))
+}
+
+/// The number of columns per strip of `step_parallel()`, narrower fires are stepped on a single thread.
+#[cfg(feature = "rayon")]
+const STRIP_WIDTH: usize = 64;
+
+/// Like `step()`, but splits the fire into strips of about 64 columns that are stepped in parallel on the rayon
+/// thread pool, for large fires. Every strip draws from its own generator, seeded from `rng`.
+///
+/// The strips are stepped in two passes, first every even strip and then every odd one, so heat crossing into
+/// a neighbouring strip is never written while that strip is being stepped. The fire burns the same as with
+/// `step()` but not with the same numbers; for the same seed and width it is the same on any number of threads.
+/// # Examples
+/// ```
+/// use doomfire::kernel::{self, Params};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let (width, height) = (1920, 1080);
+/// let mut heat = vec![0u8; width * height];
+/// for pixel in &mut heat[(height - 1) * width..] {
+///     *pixel = 36;
+/// }
+/// let mut rng = StdRng::seed_from_u64(7);
+/// for _ in 0..10 {
+///     kernel::step_parallel(&mut heat, width, height, &Params::default(), &mut rng);
+/// }
+/// assert!(heat[(height - 2) * width..(height - 1) * width].iter().any(|&h| h > 0));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn step_parallel<H: Heat + Send, R: Rng>(
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    params: &Params,
+    rng: &mut R,
+) {
+    step_strips(heat, width, height, params, rng.gen());
+}
+
+/// `step_parallel()` with the generators of the strips seeded from `seed`,
+/// returns the highest row given heat, `height` if none.
+#[cfg(feature = "rayon")]
+pub(crate) fn step_strips<H: Heat + Send>(
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    params: &Params,
+    seed: u64,
+) -> usize {
+    assert!(heat.len() >= width * height);
+    // An even number of strips, so with `wrap` the first and the last strip are never stepped at the same time.
+    let strips = (width / STRIP_WIDTH) & !1;
+    let cells = Strips {
+        ptr: heat.as_mut_ptr(),
+        len: width * height,
+    };
+    let step_strip = |strip: usize| {
+        let mut cells = cells;
+        let mut rng = Pcg64Mcg::seed_from_u64(seed.wrapping_add(strip as u64));
+        (strip * width / strips.max(1)..(strip + 1) * width / strips.max(1))
+            .map(|x| step_cells(&mut cells, width, height, x, params, &mut rng))
+            .min()
+            .unwrap_or(height)
+    };
+    if strips < 2 {
+        return step_strip(0);
+    }
+    (0..2)
+        .map(|pass| {
+            (0..strips / 2)
+                .into_par_iter()
+                .map(|i| step_strip(2 * i + pass))
+                .min()
+                .unwrap_or(height)
+        })
+        .min()
+        .unwrap_or(height)
+}
+
+/// The heat of a fire shared by the strips of `step_strips()`.
+///
+/// A column only reads itself and writes at most 2 columns to its left and 1 to its right. Strips are at least
+/// `STRIP_WIDTH` columns wide and stepped with a strip in between, so the strips of a pass touch disjoint pixels.
+#[cfg(feature = "rayon")]
+#[derive(Clone, Copy)]
+struct Strips<H> {
+    ptr: *mut H,
+    len: usize,
+}
+
+// Safety: the strips stepped at the same time never touch the same pixels, see above.
+#[cfg(feature = "rayon")]
+unsafe impl<H: Send> Send for Strips<H> {}
+#[cfg(feature = "rayon")]
+unsafe impl<H: Send> Sync for Strips<H> {}
+
+#[cfg(feature = "rayon")]
+impl<H: Copy> Cells<H> for Strips<H> {
+    #[inline(always)]
+    fn get(&self, i: usize) -> H {
+        debug_assert!(i < self.len);
+        // Safety: in bounds as `i` is a pixel of the fire, and no other strip writes this pixel during the pass.
+        unsafe { self.ptr.add(i).read() }
+    }
+
+    #[inline(always)]
+    fn set(&mut self, i: usize, heat: H) {
+        debug_assert!(i < self.len);
+        // Safety: in bounds as `i` is a pixel of the fire, and no other strip reads or writes this pixel during the pass.
+        unsafe { self.ptr.add(i).write(heat) }
+    }
+}
+
+/// Returns column `x` moved by `dx`, wrapped around or clamped to the edges.
+pub(crate) fn offset_x(wrap: bool, width: usize, x: usize, dx: isize) -> usize {
+    let x = x as isize + dx;
+    if wrap {
+        x.rem_euclid(width as isize) as usize
+    } else {
+        x.clamp(0, width as isize - 1) as usize
+    }
+}
+
+/// Returns the draw deciding the sideways drift of the heat at `(x, y)`,
+/// `rand` replaced by a gust of wind every now and then.
+fn blow<R: Rng>(
+    params: &Params,
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+    rand: usize,
+    rng: &mut R,
+) -> usize {
+    let map = params.wind_map;
+    let local = if map.len() == width * height {
+        map[y * width + x]
+    } else if map.len() == width {
+        map[x]
+    } else {
+        0
+    };
+    let wind = (params.wind + local as f32 / 127.0).clamp(-1.0, 1.0);
+    if wind != 0.0 && rng.gen::<f32>() < wind.abs() {
+        if wind > 0.0 {
+            0
+        } else {
+            2
+        }
+    } else {
+        rand
+    }
+}
+
+/// Returns how much the heat of a pixel moving up from row `y` cools down, `rand` being its draw of the distribution.
+fn cooling_at<R: Rng>(params: &Params, height: usize, y: usize, rand: usize, rng: &mut R) -> usize {
+    let curve = params.cooling_curve.filter(|curve| !curve.is_empty());
+    let probability = match (curve, params.cooling) {
+        (None, None) => return rand & 1,
+        (None, Some(probability)) => probability,
+        (Some(curve), probability) => {
+            let row = (height - 1 - y) * curve.len() / height;
+            (probability.unwrap_or(0.5) * curve[row]).max(0.0)
+        }
+    };
+    probability as usize + (rng.gen::<f32>() < probability.fract()) as usize
+}
@@ -7,8 +7,10 @@
 //!
 //! # Example
 //! ```
+//! # use doomfire::Doomfire;
+//! # let mut pixel_buffer = vec![0; 600 * 400 * 4];
 //! // Create a doomfire instance with a width of 600 and height of 400.
-//! let mut doomfire = Doomfire::new(600, 400);
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
 //! // Ignite the fire to jumpstart the algorithm;
 //! doomfire.ignite();
 //! // Doomfire copies the color values to the `&mut [u8]` rgba pixel buffer
@@ -19,7 +21,141 @@
 //! // To stop the fire algorithm call extinguish.
 //! doomfire.extinguish();
 //! ```
-use rand::{rngs::ThreadRng, Rng};
+//!
+//! # Allocations
+//! Once a fire is constructed, simulating and drawing it never allocates, so it's safe to run on real-time audio or
+//! video threads and on embedded targets with a fixed heap. That covers `update()`, `update_and_draw()`, `draw()`
+//! and its `draw_*` variants, and editing the heat (`set_heat()`, `heat_line()`, `copy_region_from()`, ...).
+//! Post processing passes that need scratch buffers (`Bloom`), and `SimulationMode::DoubleBuffered`, allocate them
+//! on the first frame and reuse them as long as the size doesn't change.
+//!
+//! Configuration (`set_*`, `add_emitter()`, `FireConfig`), `analytics()` and the exporters may allocate,
+//! and so may the rayon thread pool behind `set_parallel()`.
+//! ```
+//! # use doomfire::{format::Rgb565, postfx::{Bloom, Crt}, Doomfire, Emitter, Mono};
+//! # use std::alloc::{GlobalAlloc, Layout, System};
+//! # use std::sync::atomic::{AtomicUsize, Ordering};
+//! # struct Counting;
+//! # static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+//! # unsafe impl GlobalAlloc for Counting {
+//! #     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+//! #         ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+//! #         System.alloc(layout)
+//! #     }
+//! #     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+//! #         System.dealloc(ptr, layout)
+//! #     }
+//! # }
+//! # #[global_allocator]
+//! # static ALLOCATOR: Counting = Counting;
+//! let mut doomfire = Doomfire::from_seed(320, 200, 1);
+//! doomfire.ignite();
+//! doomfire.set_wrap(true);
+//! doomfire.set_spread(0.8, 0.9);
+//! doomfire.set_cooling_curve_fn(|height| 1.0 + height);
+//! doomfire.set_embers(2, 6);
+//! doomfire.add_emitter(Emitter::polyline(&[(10.0, 150.0), (300.0, 120.0)], 2.0, 36));
+//! doomfire.postfx_mut().push(Bloom::new(180, 4, 0.8)).push(Crt::default());
+//!
+//! let mut frame = vec![0; 320 * 200 * 4];
+//! let mut scaled = vec![0; 640 * 400 * 4];
+//! let mut mono = vec![0; 320 / 8 * 200];
+//! let mut rgb565 = vec![0; 320 * 200];
+//! // The first frame lets post processing allocate its scratch buffers.
+//! doomfire.update_and_draw(&mut frame);
+//!
+//! let before = ALLOCATIONS.load(Ordering::SeqCst);
+//! for i in 0..50 {
+//!     doomfire.update();
+//!     doomfire.draw(&mut frame);
+//!     doomfire.update_and_draw(&mut frame);
+//!     doomfire.draw_scaled(&mut scaled, 2, 2);
+//!     doomfire.draw_interlaced(&mut frame, i);
+//!     doomfire.draw_mono(&mut mono, Mono::Dither);
+//!     doomfire.draw_into::<Rgb565>(&mut rgb565);
+//!     doomfire.heat_circle((160.0, 100.0), 5.0, 36);
+//!     doomfire.flame_height();
+//! }
+//! assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), before);
+//! ```
+//!
+//! # `no_std`
+//! With `default-features = false` the crate only needs `alloc`, e.g. for firmware driving an LCD.
+//! There is no entropy to seed the fires from, so they are created with `from_seed()` or `with_rng()`,
+//! and `FireConfig`, `control` and `export` are left out. For the same reason the examples use `from_seed()`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{mem, time::Duration};
+#[cfg(not(any(feature = "std", test)))]
+use math::Float;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+
+pub use analytics::{Analytics, MemoryReport};
+pub use builder::DoomfireBuilder;
+#[cfg(feature = "std")]
+pub use config::FireConfig;
+pub use distribution::Distribution;
+pub use draw::Mono;
+pub use emitter::Emitter;
+pub use fireball::Fireball;
+pub use format::PixelFormat;
+pub use heat::Rect;
+pub use mode::SimulationMode;
+pub use palettes::Palette;
+pub use rain::Rain;
+
+mod advection;
+mod analytics;
+#[cfg(feature = "audio")]
+pub mod audio;
+mod builder;
+#[cfg(feature = "std")]
+pub mod config;
+#[cfg(feature = "std")]
+pub mod control;
+pub mod delta;
+mod distribution;
+mod draw;
+pub mod emitter;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fireball;
+pub mod format;
+mod grading;
+mod heat;
+mod hooks;
+pub mod kernel;
+#[cfg(not(any(feature = "std", test)))]
+mod math;
+mod mode;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+mod noise;
+#[cfg(feature = "osc")]
+pub mod osc;
+pub mod pacing;
+pub mod palettes;
+pub mod postfx;
+#[cfg(feature = "ron")]
+pub mod presets;
+pub mod rain;
+mod rng;
+#[cfg(feature = "rhai")]
+pub mod script;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "wad")]
+pub mod wad;
+#[cfg(feature = "web")]
+pub mod web;
+#[cfg(feature = "wgpu-interop")]
+pub mod wgpu_interop;
 
 /// The rgba color palette with 37 color values from black to red to orange to yellow to white.
 pub const PALETTE: [[u8; 4]; 37] = [
@@ -63,13 +199,83 @@ pub const PALETTE: [[u8; 4]; 37] = [
 ];
 
 /// Represents the doomfire.
+///
+/// A fire is `Send`, so it can be moved to (and updated on) a render thread.
+/// ```
+/// # use doomfire::Doomfire;
+/// fn assert_send<T: Send>() {}
+/// assert_send::<Doomfire>();
+/// ```
 pub struct Doomfire {
     width: usize,
     height: usize,
     /// Returns whether the fire is lit e.g. whether `ignite()` (true) or `extinguish()` (false) was called last.
     pub is_lit: bool,
-    fire_pixels: Vec<usize>,
-    rng: ThreadRng,
+    // How hot the bottom row burns while lit, see `set_intensity()`.
+    intensity: f32,
+    // The column `ignite_from()` lit and how far the bottom row burns from it, until the whole row does.
+    ignition: Option<(usize, f32)>,
+    // The number of pixels the ignition spreads every update, see `set_ignition_speed()`.
+    ignition_speed: f32,
+    // How uneven the bottom row burns and the seed of its noise, see `ignite_rough()`.
+    roughness: Option<(f32, u32)>,
+    // How much the bottom row flickers, the seed of its noise and how far along the noise it is, see `set_flicker()`.
+    flicker: (f32, u32, f32),
+    fire_pixels: Vec<u8>,
+    // The rows above it are without heat, kept up to date by everything heating the fire so drawing can skip them.
+    heat_top: usize,
+    palette: [[u8; 4]; 37],
+    // The palette with `temperature` and `adjustments` applied, looked up when drawing.
+    colors: [[u8; 4]; 37],
+    // The first row with heat at the last `draw_dirty()`, `None` to draw every row the next time.
+    drawn_top: Option<usize>,
+    temperature: f32,
+    // Brightness, contrast and gamma of the drawn colors, see `set_adjustments()`.
+    adjustments: (f32, f32, f32),
+    wrap: bool,
+    distribution: Distribution,
+    // Probabilities of drifting sideways and climbing a row, see `set_spread()`.
+    spread: (f32, f32),
+    wind: f32,
+    // Wind per column or pixel on top of `wind`, see `set_wind_map()`.
+    wind_map: Vec<i8>,
+    // Which way is down in the real world, see `set_gravity()`.
+    gravity: Option<(f32, f32)>,
+    cooling: Option<f32>,
+    // Cooling multipliers from the bottom row up, see `set_cooling_curve()`.
+    cooling_curve: Option<Vec<f32>>,
+    postfx: postfx::PostFx,
+    emitters: Vec<Emitter>,
+    // The number of bottom rows kept at a minimum heat, and that heat, see `set_embers()`.
+    embers: (usize, usize),
+    rain: Option<Rain>,
+    // The column and row of every falling droplet.
+    droplets: Vec<(usize, f32)>,
+    fireballs: Vec<Fireball>,
+    // Velocities in pixels per update for every pixel, see `set_velocity_field()`.
+    velocity_field: Option<Vec<(f32, f32)>>,
+    // The heat before advecting, reused every update.
+    advected: Vec<u8>,
+    // Scratch space for stepping the rows, see `kernel::step()`.
+    scratch: Vec<u8>,
+    // How `update()` moves the heat, see `set_simulation_mode()`.
+    mode: SimulationMode,
+    // The heat before the step when double buffered, reused every update.
+    previous: Vec<u8>,
+    // The time between updates of `update_dt()`, and the time passed that wasn't simulated yet
+    // by `update_dt()` or a `Pacer`.
+    tick: Duration,
+    unsimulated: Duration,
+    hooks: hooks::Hooks,
+    // Whether `update()` steps strips of columns in parallel, see `set_parallel()`.
+    #[cfg(feature = "rayon")]
+    parallel: bool,
+    // Whether `update()` takes the vectorized path, see `set_simd()`.
+    #[cfg(feature = "simd")]
+    simd: bool,
+    // Unless replaced with `with_rng()` what `SmallRng` is on 64-bit platforms, but on every platform,
+    // so fires draw the same numbers everywhere.
+    rng: rng::FireRng,
 }
 
 impl Doomfire {
@@ -77,87 +283,708 @@ impl Doomfire {
     /// The width and height needs to be the same as the pixel buffer you'll use.
     /// # Examples
     /// ```
+    /// # use doomfire::Doomfire;
     /// let mut doomfire = Doomfire::new(600, 400);
     /// ```
+    #[cfg(feature = "std")]
     pub fn new(width: usize, height: usize) -> Doomfire {
+        // Initialise random number generator
+        Doomfire::with_fire_rng(width, height, rng::FireRng::Pcg(Pcg64Mcg::from_entropy()))
+    }
+
+    /// Returns a new (not yet ignited) fire drawing its random numbers from `rng`, what all constructors end up in.
+    fn with_fire_rng(width: usize, height: usize, rng: rng::FireRng) -> Doomfire {
         // Initialze fire pixels to 0 (black).
         let fire_pixels = vec![0; width * height];
 
-        // Initialise random number generator
-        let rng = rand::thread_rng();
-
         Doomfire {
             width,
             height,
             is_lit: false,
+            intensity: 1.0,
+            ignition: None,
+            ignition_speed: 2.0,
+            roughness: None,
+            flicker: (0.0, 0, 0.0),
             fire_pixels,
+            heat_top: height,
+            palette: PALETTE,
+            colors: PALETTE,
+            drawn_top: None,
+            temperature: 0.0,
+            adjustments: (0.0, 1.0, 1.0),
+            wrap: false,
+            distribution: Distribution::Classic,
+            spread: (1.0, 1.0),
+            wind: 0.0,
+            wind_map: Vec::new(),
+            gravity: None,
+            cooling: None,
+            cooling_curve: None,
+            postfx: postfx::PostFx::new(),
+            emitters: Vec::new(),
+            embers: (0, 0),
+            rain: None,
+            droplets: Vec::new(),
+            fireballs: Vec::new(),
+            velocity_field: None,
+            advected: Vec::new(),
+            scratch: vec![0; kernel::SCRATCH_ROWS * width],
+            mode: SimulationMode::InPlace,
+            previous: Vec::new(),
+            tick: Duration::from_secs(1) / pacing::DEFAULT_RATE,
+            unsimulated: Duration::from_secs(0),
+            hooks: hooks::Hooks::default(),
+            #[cfg(feature = "rayon")]
+            parallel: false,
+            #[cfg(feature = "simd")]
+            simd: false,
             rng,
         }
     }
 
+    /// Returns a new fire like `new()` drawn with `palette` instead of `PALETTE`, see `set_palette()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // A cold blue flame for an ice level.
+    /// let doomfire = Doomfire::with_palette(600, 400, |heat: usize, max_heat: usize| {
+    ///     [0, (heat * 128 / max_heat) as u8, (heat * 255 / max_heat) as u8, 0xFF]
+    /// });
+    /// assert_eq!(doomfire.palette()[36], [0, 128, 255, 0xFF]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_palette<P: Palette>(width: usize, height: usize, palette: P) -> Doomfire {
+        let mut fire = Doomfire::new(width, height);
+        fire.set_palette(palette);
+        fire
+    }
+
+    /// Returns a new fire like `new()` whose random numbers are seeded by `seed` instead of the entropy of the system.
+    ///
+    /// Fires with the same seed, size and settings burn exactly the same on every platform, update for update,
+    /// as long as they are edited the same way in between, e.g. for a lobby background that every player of a
+    /// multiplayer game sees the same. The output below is pinned, any change to it is a breaking change.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(64, 48, 42);
+    /// doomfire.ignite();
+    /// let mut checksums = Vec::new();
+    /// for _ in 0..3 {
+    ///     for _ in 0..10 {
+    ///         doomfire.update();
+    ///     }
+    ///     // FNV-1a over the heat.
+    ///     let checksum = doomfire
+    ///         .heat()
+    ///         .iter()
+    ///         .fold(0xcbf29ce484222325u64, |hash, &heat| (hash ^ heat as u64).wrapping_mul(0x100000001b3));
+    ///     checksums.push(checksum);
+    /// }
+    /// assert_eq!(checksums, [0x19519fa1654060a5, 0x8ff648aef7665790, 0x5230b5fd1ec12ded]);
+    /// ```
+    pub fn from_seed(width: usize, height: usize, seed: u64) -> Doomfire {
+        Doomfire::with_fire_rng(
+            width,
+            height,
+            rng::FireRng::Pcg(Pcg64Mcg::seed_from_u64(seed)),
+        )
+    }
+
+    /// Returns a new fire like `new()` drawing its random numbers from `rng` instead of the built-in generator,
+    /// e.g. a `StdRng` or a mock in tests. Other generators are called through a `dyn RngCore`,
+    /// which makes `update()` slower than with the built-in one.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut a = Doomfire::with_rng(600, 400, StdRng::seed_from_u64(1));
+    /// let mut b = Doomfire::with_rng(600, 400, StdRng::seed_from_u64(1));
+    /// a.ignite();
+    /// b.ignite();
+    /// for _ in 0..10 {
+    ///     a.update();
+    ///     b.update();
+    /// }
+    /// assert_eq!(a.heat(), b.heat());
+    /// ```
+    pub fn with_rng<R: RngCore + Send + 'static>(width: usize, height: usize, rng: R) -> Doomfire {
+        Doomfire::with_fire_rng(width, height, rng::FireRng::Custom(Box::new(rng)))
+    }
+
+    /// Returns a `max_width` x `max_height` fire that can later be resized to any size with as many pixels or less
+    /// without allocating, see `resize_within_capacity()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// // Room for a fullscreen fire, starting out in a small window.
+    /// let mut doomfire = Doomfire::with_max_size(1920, 1080);
+    /// assert!(doomfire.resize_within_capacity(640, 360));
+    /// assert!(!doomfire.resize_within_capacity(3840, 2160));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_max_size(max_width: usize, max_height: usize) -> Doomfire {
+        let mut fire = Doomfire::new(0, 0);
+        fire.fire_pixels = Vec::with_capacity(max_width * max_height);
+        fire.scratch = Vec::with_capacity(kernel::SCRATCH_ROWS * max_width);
+        fire.resize_within_capacity(max_width, max_height);
+        fire
+    }
+
+    /// Returns the number of pixels the fire can be resized to without allocating.
+    pub fn capacity(&self) -> usize {
+        self.fire_pixels.capacity()
+    }
+
+    /// Resizes the fire if `width * height` fits in `capacity()`, returning whether it did.
+    /// The fire restarts from black (and is reignited if it was lit), without allocating
+    /// unless it's wider than the fire of `with_max_size()`.
+    pub fn resize_within_capacity(&mut self, width: usize, height: usize) -> bool {
+        if width * height > self.fire_pixels.capacity() {
+            return false;
+        }
+        self.fire_pixels.clear();
+        self.fire_pixels.resize(width * height, 0);
+        self.scratch.resize(kernel::SCRATCH_ROWS * width, 0);
+        self.width = width;
+        self.height = height;
+        self.heat_top = height;
+        self.drawn_top = None;
+        self.embers.0 = self.embers.0.min(height);
+        self.droplets.clear();
+        // Restarting from black, a lit fire is reignited all at once.
+        self.ignition = None;
+        if self.is_lit && height > 0 {
+            self.ignite();
+        }
+        true
+    }
+
+    /// Resizes the fire to `width` x `height`, stretching the heat so the flames keep burning instead of
+    /// restarting from black, e.g. when the window showing the fire is resized.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.ignite();
+    /// for _ in 0..100 {
+    ///     doomfire.update();
+    /// }
+    /// let flames = doomfire.analytics().max_height();
+    /// doomfire.resize(800, 600);
+    /// assert_eq!(doomfire.heat_at(400, 599), Some(36));
+    /// // The flames are stretched with the fire.
+    /// assert!(doomfire.analytics().max_height() > flames);
+    /// ```
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let (old_width, old_height) = (self.width, self.height);
+        let mut resized = vec![0; width * height];
+        if old_width > 0 && old_height > 0 {
+            // Every new pixel takes the heat of the old pixel it covers.
+            for (y, row) in resized.chunks_exact_mut(width.max(1)).enumerate() {
+                let old_row = y * old_height / height * old_width;
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    *pixel = self.fire_pixels[old_row + x * old_width / width];
+                }
+            }
+        }
+        self.fire_pixels = resized;
+        self.scratch = vec![0; kernel::SCRATCH_ROWS * width];
+        self.width = width;
+        self.height = height;
+        self.heat_top = 0;
+        self.find_heat_top();
+        self.drawn_top = None;
+        self.embers.0 = self.embers.0.min(height);
+        self.droplets.clear();
+        self.reserve_droplets();
+        // An ignition spreading along the bottom row is stretched with it, there's nothing to spread along
+        // without columns.
+        self.ignition = match self.ignition {
+            Some((x, reach)) if width > 0 => {
+                let stretch = width as f32 / old_width.max(1) as f32;
+                Some((
+                    (x * width / old_width.max(1)).min(width - 1),
+                    reach * stretch,
+                ))
+            }
+            _ => None,
+        };
+        if self.velocity_field.is_some() {
+            self.advected.reserve(self.fire_pixels.capacity());
+        }
+    }
+
     /// Updates the fire a single step.
     /// # Examples
     /// ```
-    /// let mut doomfire = Doomfire::new(600, 400);
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
     /// doomfire.update();
     /// ```
     pub fn update(&mut self) {
-        // Calculating max index here so it doesn't have to be calculated every iteration.
-        let max_idx = self.width * self.height - 1;
-        for x in 0..self.width {
-            for y in 1..self.height {
-                let src_idx = y * self.width + x;
-                let src_pixel = self.fire_pixels[src_idx];
-                // - width = "1 up"
-                let dst_idx = src_idx - self.width;
-                // Don't decrease if already 0, otherwise negative overflow.
-                if src_pixel == 0 {
-                    self.fire_pixels[dst_idx] = 0;
-                } else {
-                    // Using turbofish syntax to tell round to give f64 to round()
-                    // after round converting to usize
-                    let rand = self.rng.gen_range::<f64, f64, f64>(0.0, 3.0).round() as usize & 3;
-                    // When is_lit: use infite algorithm, when !is_lit: use algorithm that dies out.
-                    if self.is_lit {
-                        // give dst_idx a random change to go left/right
-                        let dst_idx = (src_idx - rand + 1) - self.width;
-                        self.fire_pixels[dst_idx] = src_pixel - (rand & 1);
-                    } else {
-                        // not sure why but this if branch cuts performance in half??
-                        let rand2 =
-                            self.rng.gen_range::<f64, f64, f64>(0.0, 3.0).round() as usize & 3;
-                        let dst_idx = (src_idx - rand + 1) - self.width * rand2;
-                        // Clamping the index so no overflow is possible.
-                        let dst_idx = if dst_idx > max_idx { max_idx } else { dst_idx };
-                        self.fire_pixels[dst_idx] = src_pixel - (rand & 1);
-                    }
-                }
+        self.spread_ignition();
+        self.apply_flicker();
+        self.apply_rain();
+        self.apply_fireballs();
+        self.apply_velocity_field();
+        self.step_heat();
+
+        self.apply_embers();
+        self.apply_emitters();
+        self.find_heat_top();
+        self.run_update_hooks();
+    }
+
+    /// Updates the fire `n` steps, the same as calling `update()` `n` times, e.g. to catch up after the window
+    /// was minimized. Callbacks registered with `on_update()` run after every step. When the heat moving up is
+    /// all that happens in an update (no spreading ignition, flicker, rain, fireballs, velocity field, embers,
+    /// emitters or callbacks) the rows are stepped back to back, reading the settings once.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut a = Doomfire::from_seed(320, 200, 1);
+    /// let mut b = Doomfire::from_seed(320, 200, 1);
+    /// a.ignite();
+    /// b.ignite();
+    /// a.update_n(100);
+    /// for _ in 0..100 {
+    ///     b.update();
+    /// }
+    /// assert_eq!(a.heat(), b.heat());
+    /// ```
+    pub fn update_n(&mut self, n: usize) {
+        if !self.only_steps_rows() {
+            for _ in 0..n {
+                self.update();
             }
+            return;
         }
+        self.heat_top = self.with_buffers(|fire, heat, scratch, rng| match rng {
+            rng::FireRng::Pcg(rng) => fire.step_rows_n(heat, scratch, rng, n),
+            rng::FireRng::Custom(rng) => fire.step_rows_n(heat, scratch, rng, n),
+        });
+    }
+
+    /// Returns whether `update()` does nothing but step the rows, see `update_n()`.
+    fn only_steps_rows(&self) -> bool {
+        self.steps_rows()
+            && self.ignition.is_none()
+            && !(self.flicker.0 > 0.0 && self.is_lit)
+            && self.rain.is_none()
+            && self.fireballs.is_empty()
+            && self.velocity_field.is_none()
+            && (self.embers.0 == 0 || self.embers.1 == 0)
+            && self.emitters.is_empty()
+            && !self.has_update_hooks()
+    }
+
+    /// Steps the rows `n` times with the buffers moved out by `with_buffers()`, returns the first row with heat.
+    fn step_rows_n<R: Rng>(
+        &self,
+        heat: &mut [u8],
+        scratch: &mut [u8],
+        rng: &mut R,
+        n: usize,
+    ) -> usize {
+        let (width, height) = (self.width, self.height);
+        let params = self.params();
+        let mut top = self.heat_top;
+        for _ in 0..n {
+            let heated =
+                kernel::step_rows(heat, width, height, &params, rng, scratch, top, |_, _| ());
+            top = kernel::heat_top(heat, width, height, top.min(heated));
+        }
+        top
+    }
+
+    /// Lowers the first row that may have heat to `y`, for everything heating the fire.
+    pub(crate) fn heated_from(&mut self, y: usize) {
+        self.heat_top = self.heat_top.min(y);
+    }
+
+    /// Moves the first row that may have heat down to the first one that has heat, after a step.
+    pub(crate) fn find_heat_top(&mut self) {
+        // The rows above were black before the step and got no heat from it, so only the rows that lost their heat
+        // are visited.
+        self.heat_top = kernel::heat_top(&self.fire_pixels, self.width, self.height, self.heat_top);
+    }
+
+    /// Moves the heat of every pixel up, double buffered or on the vectorized or parallel path if turned on.
+    fn step_heat(&mut self) {
+        if self.mode == SimulationMode::DoubleBuffered {
+            self.update_buffered();
+            return;
+        }
+        #[cfg(feature = "simd")]
+        if self.update_simd() {
+            return;
+        }
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            self.update_strips();
+            return;
+        }
+        self.update_rows(|_, _| ());
+    }
+
+    /// Returns whether `update()` steps the rows one by one, so `update_and_draw()` can draw them as they finish.
+    pub(crate) fn steps_rows(&self) -> bool {
+        if self.mode == SimulationMode::DoubleBuffered {
+            return false;
+        }
+        #[cfg(feature = "simd")]
+        if self.simd {
+            return false;
+        }
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            return false;
+        }
+        true
+    }
+
+    /// Returns the settings the kernel steps the fire with, tilted by `set_gravity()`.
+    pub(crate) fn params(&self) -> kernel::Params<'_> {
+        let params = kernel::Params {
+            lit: self.is_lit,
+            wrap: self.wrap,
+            distribution: self.distribution,
+            spread: self.spread,
+            wind: self.wind,
+            wind_map: &self.wind_map,
+            cooling: self.cooling,
+            cooling_curve: self.cooling_curve.as_deref(),
+        };
+        match self.gravity {
+            Some(gravity) => params.tilted(gravity),
+            None => params,
+        }
+    }
+
+    /// Runs `step` with the heat, the scratch space and the random number generator moved out of the fire,
+    /// so it can write to them while reading the settings with `params()`.
+    pub(crate) fn with_buffers<T>(
+        &mut self,
+        step: impl FnOnce(&Doomfire, &mut Vec<u8>, &mut Vec<u8>, &mut rng::FireRng) -> T,
+    ) -> T {
+        let mut heat = mem::take(&mut self.fire_pixels);
+        let mut scratch = mem::take(&mut self.scratch);
+        // Seeding a generator is cheaper than boxing one, and it's never drawn from.
+        let mut rng = mem::replace(&mut self.rng, rng::FireRng::Pcg(Pcg64Mcg::new(0)));
+        let result = step(self, &mut heat, &mut scratch, &mut rng);
+        self.fire_pixels = heat;
+        self.scratch = scratch;
+        self.rng = rng;
+        result
+    }
+
+    /// Moves the heat of every row up, calling `finished` with every row that's done, see `kernel::step()`.
+    pub(crate) fn update_rows(&mut self, finished: impl FnMut(&mut [u8], usize)) {
+        let top = self.with_buffers(|fire, heat, scratch, rng| {
+            let (width, height, cold) = (fire.width, fire.height, fire.heat_top);
+            let params = fire.params();
+            match rng {
+                rng::FireRng::Pcg(rng) => {
+                    kernel::step_rows(heat, width, height, &params, rng, scratch, cold, finished)
+                }
+                rng::FireRng::Custom(rng) => {
+                    kernel::step_rows(heat, width, height, &params, rng, scratch, cold, finished)
+                }
+            }
+        });
+        self.heated_from(top);
+    }
+
+    /// Moves the heat of every column up in parallel strips, see `kernel::step_parallel()`.
+    #[cfg(feature = "rayon")]
+    fn update_strips(&mut self) {
+        let top = self.with_buffers(|fire, heat, _, rng| {
+            let seed = rng.gen();
+            kernel::step_strips(heat, fire.width, fire.height, &fire.params(), seed)
+        });
+        self.heated_from(top);
+    }
+
+    /// Returns whether `update()` runs on every core, see `set_parallel()`.
+    #[cfg(feature = "rayon")]
+    pub fn parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// When `parallel` is true `update()` splits the fire into strips of columns that are updated in parallel
+    /// on the rayon thread pool, which pays off from about full HD. The flames look the same, but the fire no longer
+    /// draws the same random numbers as a single threaded one with the same seed. The default is false.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::new(3840, 2160);
+    /// doomfire.set_parallel(true);
+    /// doomfire.ignite();
+    /// for _ in 0..10 {
+    ///     doomfire.update();
+    /// }
+    /// assert!(doomfire.heat()[2158 * 3840..2159 * 3840].iter().any(|&heat| heat > 0));
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    /// Returns whether heat leaving one side of the fire enters on the other side, see `set_wrap()`.
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// When `wrap` is true heat leaving the left edge enters on the right and vice versa,
+    /// making the fire horizontally tileable, e.g. for repeating backgrounds or cylindrical LED installations.
+    /// When false (the default) heat is stopped at the edges.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_wrap(true);
+    /// ```
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
     }
 
-    /// Copies the color values to the supplied `&mut [u8]` rgba pixel buffer.
+    /// Copies the color values to the supplied `&mut [u8]` rgba pixel buffer and runs the post processing passes on it.
     /// The same width and height values are to be used for the fire and pixel buffer.
     /// # Examples
     /// ```
-    /// let mut doomfire = Doomfire::new(600, 400);
-    /// let pixel_buffer: &mut [u8] = some_pixel_buffer_generator(600, 400) ;
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let pixel_buffer: &mut [u8] = &mut vec![0; 600 * 400 * 4];
     /// doomfire.draw(pixel_buffer);
     /// ```
     pub fn draw(&self, frame: &mut [u8]) {
-        for (i, pixel) in frame.chunks_exact_mut(4).enumerate() {
-            pixel.copy_from_slice(&PALETTE[self.fire_pixels[i]]);
+        self.draw_into::<format::Rgba8>(frame);
+        self.postfx.apply(frame, self.width, self.height);
+    }
+
+    /// Returns the width of the fire in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the fire in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the heat (palette index) of every pixel, row by row starting at the top left.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.ignite();
+    /// // The bottom row is white hot.
+    /// assert_eq!(doomfire.heat()[399 * 600], 36);
+    /// ```
+    pub fn heat(&self) -> &[u8] {
+        &self.fire_pixels
+    }
+
+    /// Returns the palette used by `draw()` before `set_temperature()` and `set_adjustments()`, `PALETTE` unless `set_palette()` was called.
+    pub fn palette(&self) -> &[[u8; 4]; 37] {
+        &self.palette
+    }
+
+    /// Replaces the palette used by `draw()`.
+    /// Index 0 is the coldest (background) color and index 36 the hottest.
+    /// Besides arrays of 37 colors this takes anything implementing `Palette`,
+    /// which is evaluated once for every heat level so `draw()` only looks up colors.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Doomfire, PALETTE};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut palette = PALETTE;
+    /// palette[0] = [0x00, 0x00, 0x00, 0x00]; // Transparent background.
+    /// doomfire.set_palette(palette);
+    /// // Shorter (or longer) lists of colors are stretched over the heat levels.
+    /// doomfire.set_palette([[0x00, 0x00, 0x00, 0xFF], [0xFF, 0x00, 0x00, 0xFF], [0xFF, 0xFF, 0x00, 0xFF]]);
+    /// assert_eq!(doomfire.palette()[18], [0xFF, 0x00, 0x00, 0xFF]);
+    /// ```
+    pub fn set_palette<P: Palette>(&mut self, palette: P) {
+        let max_heat = PALETTE.len() - 1;
+        for (heat, color) in self.palette.iter_mut().enumerate() {
+            *color = palette.color(heat, max_heat);
         }
+        self.shift_colors();
     }
 
     /// Sets the bottom row pixels with white so the doomfire algorithm can start.
     pub fn ignite(&mut self) {
-        // White values (36) in bottom row.
-        for i in 0..self.width {
-            self.fire_pixels[(self.height - 1) * self.width + i] = PALETTE.len() - 1;
+        self.ignition = None;
+        self.roughness = None;
+        self.fill_bottom_row();
+        self.is_lit = true;
+        self.run_ignite_hooks();
+    }
+
+    /// Lights the bottom row at column `x` only, from where it spreads outward every update like a fuse,
+    /// instead of the whole row flashing on at once. `x` is clamped to the fire's width.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_ignition_speed(10.0);
+    /// doomfire.ignite_from(100);
+    /// assert_eq!(doomfire.heat_at(0, 399), Some(0));
+    /// for _ in 0..10 {
+    ///     doomfire.update();
+    /// }
+    /// assert_eq!(doomfire.heat_at(0, 399), Some(36));
+    /// assert_eq!(doomfire.heat_at(250, 399), Some(0));
+    /// ```
+    pub fn ignite_from(&mut self, x: usize) {
+        if self.height == 0 || self.width == 0 {
+            // There is no bottom row to spread along.
+            return self.ignite();
         }
+        let start = (self.height - 1) * self.width;
+        for pixel in &mut self.fire_pixels[start..] {
+            *pixel = 0;
+        }
+        self.ignition = Some((x.min(self.width - 1), 0.0));
+        self.roughness = None;
+        self.fill_bottom_row();
+        self.is_lit = true;
+        self.run_ignite_hooks();
+    }
 
+    /// Ignites the fire with an uneven bottom row, some stretches burning hotter than others, for a more organic base
+    /// than the uniform white line of `ignite()`. The roughness goes from 0.0 (like `ignite()`) to 1.0,
+    /// where the coolest stretches barely burn. The unevenness stays until the fire is ignited again.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.ignite_rough(0.5);
+    /// let bottom = &doomfire.heat()[399 * 600..];
+    /// assert!(bottom.iter().all(|&heat| (18..=36).contains(&heat)));
+    /// assert!(bottom.iter().any(|&heat| heat < 30));
+    /// ```
+    pub fn ignite_rough(&mut self, roughness: f32) {
+        self.ignition = None;
+        self.roughness = Some((roughness.clamp(0.0, 1.0), self.rng.gen()));
+        self.fill_bottom_row();
         self.is_lit = true;
+        self.run_ignite_hooks();
+    }
+
+    /// Returns the number of pixels an ignition spreads to both sides every update, see `set_ignition_speed()`.
+    pub fn ignition_speed(&self) -> f32 {
+        self.ignition_speed
+    }
+
+    /// Sets the number of pixels the bottom row lit by `ignite_from()` spreads to both sides every update,
+    /// fractions spread a pixel every few updates. The default is 2.0.
+    pub fn set_ignition_speed(&mut self, speed: f32) {
+        self.ignition_speed = speed.max(0.0);
+    }
+
+    /// Spreads the ignition of `ignite_from()` along the bottom row.
+    fn spread_ignition(&mut self) {
+        if let Some((x, reach)) = self.ignition {
+            let reach = reach + self.ignition_speed;
+            self.ignition = Some((x, reach));
+            self.fill_bottom_row();
+            if reach as usize >= x.max(self.width - 1 - x) {
+                self.ignition = None;
+            }
+        }
+    }
+
+    /// Returns how hot the bottom row burns while lit, see `set_intensity()`.
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Sets how hot the bottom row burns while lit, from 0.0 (black) to 1.0 (white hot, the default).
+    /// Lower intensities make lower, redder flames. A lit fire changes right away, an extinguished one on `ignite()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.ignite();
+    /// doomfire.set_intensity(0.5);
+    /// assert_eq!(doomfire.heat_at(0, 399), Some(18));
+    /// ```
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity.clamp(0.0, 1.0);
+        if self.is_lit {
+            self.fill_bottom_row();
+        }
+    }
+
+    /// Returns how much the heat of the bottom row varies every update, see `set_flicker()`.
+    pub fn flicker(&self) -> f32 {
+        self.flicker.0
+    }
+
+    /// Lets the heat of the bottom row of a lit fire slowly rise and fall for every column on its own,
+    /// so the base of the fire shimmers. The amount goes from 0.0 (a steady bottom row, the default) to 1.0,
+    /// where columns dip all the way to black. Heat added to the bottom row by hand is overwritten every update.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_flicker(0.2);
+    /// doomfire.ignite();
+    /// doomfire.update();
+    /// assert!(doomfire.heat()[399 * 600..].iter().any(|&heat| heat < 36));
+    /// ```
+    pub fn set_flicker(&mut self, flicker: f32) {
+        self.flicker = (flicker.clamp(0.0, 1.0), self.rng.gen(), self.flicker.2);
+        if self.is_lit {
+            self.fill_bottom_row();
+        }
+    }
+
+    /// Moves the flicker of the bottom row along.
+    fn apply_flicker(&mut self) {
+        // The number of updates between two random heats of a column.
+        const PERIOD: f32 = 8.0;
+        if self.flicker.0 > 0.0 && self.is_lit {
+            self.flicker.2 += 1.0 / PERIOD;
+            self.fill_bottom_row();
+        }
+    }
+
+    /// Sets the bottom row, or the part an ignition reached, to the heat of the intensity and roughness.
+    fn fill_bottom_row(&mut self) {
+        if self.height == 0 || self.width == 0 {
+            return;
+        }
+        let heat = self.intensity * (PALETTE.len() - 1) as f32;
+        let (left, right) = match self.ignition {
+            Some((x, reach)) => (x.saturating_sub(reach as usize), x + reach as usize + 1),
+            None => (0, self.width),
+        };
+        let right = right.min(self.width);
+        let start = (self.height - 1) * self.width;
+        if left < right {
+            self.heated_from(self.height - 1);
+        }
+        let row = &mut self.fire_pixels[start + left..start + right];
+        for (x, pixel) in (left..right).zip(row) {
+            let heat = match self.roughness {
+                Some((roughness, seed)) => heat * (1.0 - roughness * noise::row_noise(seed, x)),
+                None => heat,
+            };
+            let heat = match self.flicker {
+                (flicker, seed, time) if flicker > 0.0 => {
+                    let seed = seed.wrapping_add((x as u32).wrapping_mul(0x01B8_73F3));
+                    heat * (1.0 - flicker * noise::value_noise(seed, time))
+                }
+                _ => heat,
+            };
+            *pixel = heat.round() as u8;
+        }
     }
 
     /// Sets the bottom row pixels to black so the doomfire algorithm dies out.
@@ -167,11 +994,13 @@ impl Doomfire {
             self.fire_pixels[(self.height - 1) * self.width + i] = 0;
         } */
 
+        self.ignition = None;
         self.is_lit = false;
     }
 }
 
 /// Returns a new Doomfire instance width a width of 600 and height of 400.
+#[cfg(feature = "std")]
 impl Default for Doomfire {
     fn default() -> Self {
         Doomfire::new(600, 400)
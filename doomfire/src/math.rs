@@ -0,0 +1,52 @@
+//! The float math of `std` that `core` lacks, from `libm` when building without `std`.
+
+/// The `f32` methods of `std` used by the fire.
+pub(crate) trait Float {
+    fn round(self) -> f32;
+    fn floor(self) -> f32;
+    fn ceil(self) -> f32;
+    fn fract(self) -> f32;
+    fn sqrt(self) -> f32;
+    fn hypot(self, other: f32) -> f32;
+    fn ln(self) -> f32;
+    fn powf(self, n: f32) -> f32;
+    fn cos(self) -> f32;
+}
+
+impl Float for f32 {
+    fn round(self) -> f32 {
+        libm::roundf(self)
+    }
+
+    fn floor(self) -> f32 {
+        libm::floorf(self)
+    }
+
+    fn ceil(self) -> f32 {
+        libm::ceilf(self)
+    }
+
+    fn fract(self) -> f32 {
+        self - libm::truncf(self)
+    }
+
+    fn sqrt(self) -> f32 {
+        libm::sqrtf(self)
+    }
+
+    fn hypot(self, other: f32) -> f32 {
+        libm::hypotf(self, other)
+    }
+
+    fn ln(self) -> f32 {
+        libm::logf(self)
+    }
+
+    fn powf(self, n: f32) -> f32 {
+        libm::powf(self, n)
+    }
+
+    fn cos(self) -> f32 {
+        libm::cosf(self)
+    }
+}
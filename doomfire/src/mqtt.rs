@@ -0,0 +1,120 @@
+//! Controlling a fire from MQTT topics, e.g. a fire display on a wall-mounted screen or LED panel
+//! driven by a home automation setup.
+//!
+//! Topics live under a prefix, with payloads as plain text:
+//!
+//! | Topic                 | Payload                             | Command                                |
+//! |-----------------------|-------------------------------------|----------------------------------------|
+//! | `<prefix>/ignite`     | anything                            | `Doomfire::ignite()`                   |
+//! | `<prefix>/extinguish` | anything                            | `Doomfire::extinguish()`               |
+//! | `<prefix>/lit`        | `ON`/`OFF`, `true`/`false`, `1`/`0` | ignites or extinguishes, like a switch |
+//! | `<prefix>/intensity`  | 0.0 to 1.0                          | `Doomfire::set_intensity()`            |
+//! | `<prefix>/wind`       | -1.0 to 1.0                         | `Doomfire::set_wind()`                 |
+//! | `<prefix>/palette`    | a palette name                      | switches to the palette in `palettes`  |
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{mqtt::MqttControl, Doomfire};
+//! use rumqttc::MqttOptions;
+//!
+//! let options = MqttOptions::new("doomfire", "192.168.1.10", 1883);
+//! let mut control = MqttControl::connect(options, "home/livingroom/fire");
+//! let mut doomfire = Doomfire::new(600, 400);
+//! loop {
+//!     control.poll(&mut doomfire);
+//!     doomfire.update();
+//!     // draw...
+//! }
+//! ```
+use crate::{control::Command, Doomfire};
+use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait before reconnecting after the connection to the broker failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Returns the command of a message published to `topic`, `None` for topics outside of `prefix`,
+/// unknown topics or payloads that don't parse.
+/// # Examples
+/// ```
+/// # use doomfire::{control::Command, mqtt};
+/// assert_eq!(mqtt::command("fire", "fire/lit", b"OFF"), Some(Command::Extinguish));
+/// assert_eq!(mqtt::command("fire", "fire/intensity", b"0.5"), Some(Command::Intensity(0.5)));
+/// assert_eq!(mqtt::command("fire", "lamp/lit", b"ON"), None);
+/// ```
+pub fn command(prefix: &str, topic: &str, payload: &[u8]) -> Option<Command> {
+    let name = topic.strip_prefix(prefix)?.strip_prefix('/')?;
+    let payload = std::str::from_utf8(payload).ok()?.trim();
+    match name {
+        "ignite" => Some(Command::Ignite),
+        "extinguish" => Some(Command::Extinguish),
+        "lit" => match payload.to_ascii_lowercase().as_str() {
+            "on" | "true" | "1" => Some(Command::Ignite),
+            "off" | "false" | "0" => Some(Command::Extinguish),
+            _ => None,
+        },
+        "intensity" => Some(Command::Intensity(payload.parse().ok()?)),
+        "wind" => Some(Command::Wind(payload.parse().ok()?)),
+        "palette" => Some(Command::Palette(payload.to_string())),
+        _ => None,
+    }
+}
+
+/// A connection to an MQTT broker, subscribed to all topics under a prefix.
+///
+/// The connection runs on a background thread that reconnects (and subscribes again) when the broker goes away,
+/// the messages it receives are applied to a fire with `poll()`.
+pub struct MqttControl {
+    prefix: String,
+    messages: Receiver<(String, Vec<u8>)>,
+}
+
+impl MqttControl {
+    /// Connects to the broker in `options` and subscribes to `<prefix>/#`.
+    pub fn connect(options: MqttOptions, prefix: &str) -> MqttControl {
+        let (client, mut connection) = Client::new(options, 10);
+        let (sender, messages) = mpsc::channel();
+        let topic = format!("{}/#", prefix);
+        thread::spawn(move || {
+            for event in connection.iter() {
+                match event {
+                    // Subscriptions don't survive a clean session, so subscribe on every (re)connect.
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        let _ = client.try_subscribe(topic.as_str(), QoS::AtLeastOnce);
+                    }
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        // Stop once the control is dropped.
+                        if sender
+                            .send((publish.topic, publish.payload.to_vec()))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    Ok(_) => (),
+                    Err(_) => thread::sleep(RECONNECT_DELAY),
+                }
+            }
+        });
+        MqttControl {
+            prefix: prefix.to_string(),
+            messages,
+        }
+    }
+
+    /// Applies the commands of all messages received since the last call to `fire` without blocking,
+    /// and returns how many were applied. Unknown topics, payloads that don't parse and unknown palettes are skipped.
+    pub fn poll(&mut self, fire: &mut Doomfire) -> usize {
+        let mut applied = 0;
+        for (topic, payload) in self.messages.try_iter() {
+            if let Some(command) = command(&self.prefix, &topic, &payload) {
+                if fire.apply_command(&command).is_ok() {
+                    applied += 1;
+                }
+            }
+        }
+        applied
+    }
+}
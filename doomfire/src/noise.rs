@@ -0,0 +1,29 @@
+//! Smooth random noise for uneven and flickering flames, seeded so it can be evaluated again without storing it.
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+
+/// Returns a random value from 0.0 to 1.0 for knot `i` of the noise seeded with `seed`.
+fn hash(seed: u32, i: u32) -> f32 {
+    let mut h = i.wrapping_mul(0x9E37_79B1) ^ seed;
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297A_2D39);
+    h ^= h >> 15;
+    (h >> 8) as f32 / (1 << 24) as f32
+}
+
+/// Returns noise from 0.0 to 1.0 at `x`, smoothly interpolated between random values at whole numbers.
+pub(crate) fn value_noise(seed: u32, x: f32) -> f32 {
+    let i = x.floor();
+    let t = x - i;
+    let t = t * t * (3.0 - 2.0 * t);
+    let i = i as i64 as u32;
+    hash(seed, i) * (1.0 - t) + hash(seed, i.wrapping_add(1)) * t
+}
+
+/// Returns noise from 0.0 to 1.0 along a row of pixels, with bumps every 16 columns and finer detail every 4.
+pub(crate) fn row_noise(seed: u32, x: usize) -> f32 {
+    let x = x as f32;
+    value_noise(seed, x / 16.0) * 0.7 + value_noise(seed.wrapping_add(1), x / 4.0) * 0.3
+}
@@ -0,0 +1,144 @@
+//! Controlling a fire with OSC messages over UDP, e.g. from a lighting desk or TouchOSC.
+//!
+//! | Address            | Arguments            | Command                                      |
+//! |--------------------|----------------------|----------------------------------------------|
+//! | `/fire/ignite`     | none, or a value > 0 | `Doomfire::ignite()`                         |
+//! | `/fire/extinguish` | none, or a value > 0 | `Doomfire::extinguish()`                     |
+//! | `/fire/lit`        | a value              | ignites above 0.5, extinguishes otherwise    |
+//! | `/fire/intensity`  | 0.0 to 1.0           | `Doomfire::set_intensity()`                  |
+//! | `/fire/wind`       | -1.0 to 1.0          | `Doomfire::set_wind()`                       |
+//! | `/fire/palette`    | a palette name       | switches to the palette in `palettes`        |
+//!
+//! Values can be floats, doubles, ints or booleans, so both buttons and faders work.
+//! Buttons that also send 0 on release only act on the press.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{osc::OscListener, Doomfire};
+//!
+//! let mut listener = OscListener::bind("0.0.0.0:9000").unwrap();
+//! let mut doomfire = Doomfire::new(600, 400);
+//! loop {
+//!     listener.poll(&mut doomfire).unwrap();
+//!     doomfire.update();
+//!     // draw...
+//! }
+//! ```
+use crate::{control::Command, Doomfire};
+use rosc::{OscMessage, OscPacket, OscType};
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+
+/// Returns the value of an OSC argument as a float.
+fn value(arg: &OscType) -> Option<f32> {
+    match *arg {
+        OscType::Float(v) => Some(v),
+        OscType::Double(v) => Some(v as f32),
+        OscType::Int(v) => Some(v as f32),
+        OscType::Long(v) => Some(v as f32),
+        OscType::Bool(v) => Some(v as u8 as f32),
+        _ => None,
+    }
+}
+
+/// Returns the command of `message`, `None` for unknown addresses or missing arguments.
+/// # Examples
+/// ```
+/// # use doomfire::{control::Command, osc};
+/// use rosc::{OscMessage, OscType};
+///
+/// let message = OscMessage {
+///     addr: "/fire/wind".to_string(),
+///     args: vec![OscType::Float(-0.5)],
+/// };
+/// assert_eq!(osc::command(&message), Some(Command::Wind(-0.5)));
+/// ```
+pub fn command(message: &OscMessage) -> Option<Command> {
+    let first = message.args.first();
+    let pressed = first.is_none_or(|arg| value(arg).is_some_and(|v| v > 0.0));
+    match message.addr.as_str() {
+        "/fire/ignite" if pressed => Some(Command::Ignite),
+        "/fire/extinguish" if pressed => Some(Command::Extinguish),
+        "/fire/lit" if value(first?)? > 0.5 => Some(Command::Ignite),
+        "/fire/lit" => Some(Command::Extinguish),
+        "/fire/intensity" => Some(Command::Intensity(value(first?)?)),
+        "/fire/wind" => Some(Command::Wind(value(first?)?)),
+        "/fire/palette" => match first? {
+            OscType::String(name) => Some(Command::Palette(name.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Calls `f` for every message in `packet`, including those in (nested) bundles.
+fn messages<F: FnMut(&OscMessage)>(packet: &OscPacket, f: &mut F) {
+    match packet {
+        OscPacket::Message(message) => f(message),
+        OscPacket::Bundle(bundle) => {
+            for packet in &bundle.content {
+                messages(packet, f);
+            }
+        }
+    }
+}
+
+/// A UDP socket receiving OSC messages for a fire.
+pub struct OscListener {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+}
+
+impl OscListener {
+    /// Listens on `addr`, e.g. `"0.0.0.0:9000"`.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<OscListener> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(OscListener {
+            socket,
+            buffer: vec![0; rosc::decoder::MTU],
+        })
+    }
+
+    /// Returns the address the listener is bound to.
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Applies the commands of all messages received since the last call to `fire` without blocking,
+    /// and returns how many were applied. Packets that don't decode, unknown addresses and unknown palettes are skipped.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{osc::OscListener, Doomfire};
+    /// use rosc::{encoder, OscMessage, OscPacket};
+    /// use std::net::UdpSocket;
+    ///
+    /// let mut listener = OscListener::bind("127.0.0.1:0").unwrap();
+    /// let message = OscPacket::Message(OscMessage { addr: "/fire/ignite".to_string(), args: vec![] });
+    /// let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    /// sender.send_to(&encoder::encode(&message).unwrap(), listener.local_addr().unwrap()).unwrap();
+    ///
+    /// let mut doomfire = Doomfire::new(600, 400);
+    /// while listener.poll(&mut doomfire).unwrap() == 0 {}
+    /// assert!(doomfire.is_lit);
+    /// ```
+    pub fn poll(&mut self, fire: &mut Doomfire) -> io::Result<usize> {
+        let mut applied = 0;
+        loop {
+            let len = match self.socket.recv(&mut self.buffer) {
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(applied),
+                Err(e) => return Err(e),
+            };
+            if let Ok((_, packet)) = rosc::decoder::decode_udp(&self.buffer[..len]) {
+                messages(&packet, &mut |message| {
+                    if let Some(command) = command(message) {
+                        if fire.apply_command(&command).is_ok() {
+                            applied += 1;
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
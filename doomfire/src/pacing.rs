@@ -0,0 +1,195 @@
+//! Running a fire at a fixed number of updates per second regardless of the frame rate,
+//! degrading gracefully on devices that can't keep up instead of falling further and further behind.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{pacing::Pacer, Doomfire};
+//! use std::time::Instant;
+//!
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
+//! let mut frame = vec![0; 600 * 400 * 4];
+//! let mut pacer = Pacer::new(60);
+//! pacer.set_degrade(true);
+//! doomfire.ignite();
+//!
+//! let mut last = Instant::now();
+//! loop {
+//!     let now = Instant::now();
+//!     pacer.update(&mut doomfire, now - last);
+//!     last = now;
+//!     pacer.draw(&doomfire, &mut frame);
+//!     // present the frame...
+//! }
+//! ```
+use crate::Doomfire;
+use core::{convert::TryFrom, time::Duration};
+
+/// The updates per second of `Doomfire::update_dt()` unless set with `Doomfire::set_simulation_rate()`.
+pub const DEFAULT_RATE: u32 = 60;
+
+/// The number of frames in a row that need to fall behind before the pacer counts as overloaded,
+/// and that need to keep up before it recovers.
+const OVERLOAD_FRAMES: usize = 30;
+
+/// Turns the time between frames into updates of a fire, see the module documentation.
+#[derive(Debug, Clone)]
+pub struct Pacer {
+    step: Duration,
+    max_steps: usize,
+    // Goes up on every frame that fell behind and down on every frame that kept up, see `OVERLOAD_FRAMES`.
+    strain: usize,
+    overloaded: bool,
+    degrade: bool,
+    field: usize,
+}
+
+impl Pacer {
+    /// Returns a pacer updating `updates_per_second` times per second, catching up at most 4 updates per frame.
+    /// Like `Doomfire::set_simulation_rate()`, rates above a billion are clamped.
+    pub fn new(updates_per_second: u32) -> Pacer {
+        Pacer {
+            step: tick(updates_per_second),
+            max_steps: 4,
+            strain: 0,
+            overloaded: false,
+            degrade: false,
+            field: 0,
+        }
+    }
+
+    /// Returns the maximum number of updates per frame, see `set_max_steps()`.
+    pub fn max_steps(&self) -> usize {
+        self.max_steps
+    }
+
+    /// Sets the maximum number of updates run per frame. When more are due the rest is skipped,
+    /// so the fire slows down on a slow device instead of spending ever longer catching up.
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps.max(1);
+    }
+
+    /// Returns whether drawing does less work while overloaded, see `set_degrade()`.
+    pub fn degrade(&self) -> bool {
+        self.degrade
+    }
+
+    /// When `degrade` is true `draw()` only draws every other row while overloaded, alternating every frame
+    /// like `Doomfire::draw_interlaced()`, halving the bytes written until the device keeps up again.
+    pub fn set_degrade(&mut self, degrade: bool) {
+        self.degrade = degrade;
+    }
+
+    /// Returns whether the device fell behind for a while, see `update()`.
+    pub fn is_overloaded(&self) -> bool {
+        self.overloaded
+    }
+
+    /// Runs the updates due after `elapsed` since the last frame and returns how many ran.
+    /// Time left over is kept in the fire, the same as with `Doomfire::update_dt()`.
+    /// Falling behind for 30 frames in a row makes the pacer overloaded, keeping up as long makes it recover.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{pacing::Pacer, Doomfire};
+    /// # use std::time::Duration;
+    /// let mut doomfire = Doomfire::from_seed(60, 40, 1);
+    /// let mut pacer = Pacer::new(60);
+    /// assert_eq!(pacer.update(&mut doomfire, Duration::from_millis(34)), 2);
+    /// // A hiccup of a second only runs the 4 updates that fit in a frame.
+    /// assert_eq!(pacer.update(&mut doomfire, Duration::from_secs(1)), 4);
+    /// assert!(!pacer.is_overloaded());
+    /// ```
+    pub fn update(&mut self, fire: &mut Doomfire, elapsed: Duration) -> usize {
+        let (due, steps) = fire.catch_up(elapsed, self.step, self.max_steps);
+        if due > steps {
+            self.strain = (self.strain + 1).min(OVERLOAD_FRAMES);
+        } else {
+            self.strain = self.strain.saturating_sub(1);
+        }
+        if self.strain == OVERLOAD_FRAMES {
+            self.overloaded = true;
+        } else if self.strain == 0 {
+            self.overloaded = false;
+        }
+        steps
+    }
+
+    /// Draws `fire` to `frame`, only half of the rows when overloaded and `degrade()` is true.
+    /// Keep using the same frame, the rows that aren't drawn keep the previous frame.
+    pub fn draw(&mut self, fire: &Doomfire, frame: &mut [u8]) {
+        if self.degrade && self.overloaded {
+            fire.draw_interlaced(frame, self.field);
+            self.field += 1;
+        } else {
+            fire.draw(frame);
+        }
+    }
+}
+
+impl Doomfire {
+    /// Returns the updates per second of `update_dt()`, see `set_simulation_rate()`.
+    pub fn simulation_rate(&self) -> u32 {
+        (Duration::from_secs(1).as_nanos() / self.tick.as_nanos()) as u32
+    }
+
+    /// Sets how many times per second `update_dt()` updates the fire, 60 (`DEFAULT_RATE`) by default.
+    /// Rates above a billion, an update every nanosecond, are clamped.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::from_seed(60, 40, 1);
+    /// doomfire.set_simulation_rate(u32::MAX);
+    /// assert_eq!(doomfire.simulation_rate(), 1_000_000_000);
+    /// ```
+    pub fn set_simulation_rate(&mut self, updates_per_second: u32) {
+        self.tick = tick(updates_per_second);
+    }
+
+    /// Updates the fire as many times as are due after `delta` since the last call, at `simulation_rate()`,
+    /// and returns how many updates ran. Time left over is kept for the next call, so the flames move as fast
+    /// on a 144 Hz display as on a 60 Hz one. Anything beyond a second of updates is dropped, so a long pause
+    /// (a minimized window, a debugger) doesn't stall the next frame; see `Pacer` for devices that can't keep up.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// # use std::time::Duration;
+    /// let mut doomfire = Doomfire::from_seed(60, 40, 1);
+    /// doomfire.ignite();
+    /// // A second of frames 7 ms apart, about 144 Hz, still updates the fire 60 times.
+    /// let updates: usize = (0..144).map(|_| doomfire.update_dt(Duration::from_millis(7))).sum();
+    /// assert_eq!(updates, 60);
+    /// // Coming back after a minute runs at most a second of updates.
+    /// assert_eq!(doomfire.update_dt(Duration::from_secs(60)), 60);
+    /// ```
+    pub fn update_dt(&mut self, delta: Duration) -> usize {
+        let (_, steps) = self.catch_up(delta, self.tick, self.simulation_rate() as usize);
+        steps
+    }
+
+    /// Adds `delta` to the time that wasn't simulated yet and runs the updates `tick` apart that are due,
+    /// at most `max_steps`. Returns how many updates were due and how many ran.
+    pub(crate) fn catch_up(
+        &mut self,
+        delta: Duration,
+        tick: Duration,
+        max_steps: usize,
+    ) -> (usize, usize) {
+        self.unsimulated = self.unsimulated.saturating_add(delta);
+        let due =
+            usize::try_from(self.unsimulated.as_nanos() / tick.as_nanos()).unwrap_or(usize::MAX);
+        let steps = due.min(max_steps);
+        self.update_n(steps);
+        if due > steps {
+            // Drop what can't be caught up, or the backlog only grows.
+            self.unsimulated = Duration::from_secs(0);
+        } else {
+            self.unsimulated =
+                Duration::from_nanos((self.unsimulated.as_nanos() % tick.as_nanos()) as u64);
+        }
+        (due, steps)
+    }
+}
+
+/// Returns the time between updates at `updates_per_second`, at least a nanosecond.
+fn tick(updates_per_second: u32) -> Duration {
+    (Duration::from_secs(1) / updates_per_second.max(1)).max(Duration::from_nanos(1))
+}
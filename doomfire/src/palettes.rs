@@ -0,0 +1,565 @@
+//! Ready made palettes for `Doomfire::set_palette()`, and the `Palette` trait for computing colors instead.
+//!
+//! # Example
+//! ```
+//! use doomfire::{palettes, Doomfire};
+//!
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
+//! doomfire.set_palette(palettes::BLUE_YELLOW);
+//!
+//! // A gradient from black to green, computed from the heat.
+//! doomfire.set_palette(|heat: usize, max_heat: usize| {
+//!     [0, (heat * 255 / max_heat) as u8, 0, 0xFF]
+//! });
+//! ```
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use alloc::vec::Vec;
+use core::{error, fmt};
+
+/// Maps a heat from 0 to `max_heat` to an rgba color.
+///
+/// `Doomfire::set_palette()` evaluates the palette once for every heat level and `draw()` looks the colors up,
+/// so even expensive palettes don't slow down drawing.
+/// Implemented for arrays of colors of any length (stretched over the heat levels, an empty array is all black),
+/// closures taking the same arguments, `Gradient` and `Animated`.
+pub trait Palette {
+    /// Returns the color of `heat`, from 0 (coldest) to `max_heat` (hottest).
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4];
+}
+
+impl<const N: usize> Palette for [[u8; 4]; N] {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        stretch(self, heat, max_heat)
+    }
+}
+
+impl Palette for Vec<[u8; 4]> {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        stretch(self, heat, max_heat)
+    }
+}
+
+/// Stretches `colors` over the heat levels, black when there are no colors.
+fn stretch(colors: &[[u8; 4]], heat: usize, max_heat: usize) -> [u8; 4] {
+    let index = (heat * colors.len().saturating_sub(1))
+        .checked_div(max_heat)
+        .unwrap_or(0);
+    colors.get(index).copied().unwrap_or([0, 0, 0, 0xFF])
+}
+
+impl<F: Fn(usize, usize) -> [u8; 4]> Palette for F {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        self(heat, max_heat)
+    }
+}
+
+/// A palette that changes over time, pass it to `Doomfire::set_palette()` again after every `set_time()`.
+/// # Examples
+/// ```
+/// # use doomfire::{palettes::Animated, Doomfire};
+/// // A fire that slowly pulses between red and blue.
+/// let mut palette = Animated::new(|heat, max_heat, time| {
+///     let level = (heat * 255 / max_heat) as f32;
+///     let blue = (time.sin() + 1.0) / 2.0;
+///     [(level * (1.0 - blue)) as u8, 0, (level * blue) as u8, 0xFF]
+/// });
+///
+/// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+/// for frame in 0..60 {
+///     palette.set_time(frame as f32 / 60.0);
+///     doomfire.set_palette(&palette);
+///     doomfire.update();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Animated<F> {
+    color: F,
+    time: f32,
+}
+
+impl<F: Fn(usize, usize, f32) -> [u8; 4]> Animated<F> {
+    /// Returns a palette computing colors from the heat, the maximum heat and the time, starting at time 0.
+    pub fn new(color: F) -> Animated<F> {
+        Animated { color, time: 0.0 }
+    }
+
+    /// Returns the time the colors are computed for.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Sets the time the colors are computed for, in whatever unit the color function expects.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+}
+
+impl<F: Fn(usize, usize, f32) -> [u8; 4]> Palette for Animated<F> {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        (self.color)(heat, max_heat, self.time)
+    }
+}
+
+impl<F: Fn(usize, usize, f32) -> [u8; 4]> Palette for &Animated<F> {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        (*self).color(heat, max_heat)
+    }
+}
+
+/// A palette interpolated between a handful of color stops, each a position from 0.0 (coldest) to 1.0 (hottest)
+/// and an rgba color. Before the first and after the last stop the colors of those stops are kept.
+/// # Examples
+/// ```
+/// # use doomfire::{palettes::Gradient, Doomfire};
+/// // An ice blue fire.
+/// let gradient = Gradient::new(&[
+///     (0.0, [0x07, 0x07, 0x07, 0xFF]),
+///     (0.5, [0x10, 0x40, 0xC0, 0xFF]),
+///     (1.0, [0xFF, 0xFF, 0xFF, 0xFF]),
+/// ]);
+/// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+/// doomfire.set_palette(&gradient);
+/// assert_eq!(doomfire.palette()[18], [0x10, 0x40, 0xC0, 0xFF]);
+///
+/// // Or as many colors as needed elsewhere, e.g. for the lookup texture of a shader.
+/// assert_eq!(gradient.colors(256).len(), 256);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    // Sorted by position.
+    stops: Vec<(f32, [u8; 4])>,
+}
+
+impl Gradient {
+    /// Returns the gradient through `stops`, which may be in any order. Positions are clamped to 0.0 to 1.0.
+    /// # Panics
+    /// Panics if there are no stops.
+    pub fn new(stops: &[(f32, [u8; 4])]) -> Gradient {
+        assert!(!stops.is_empty(), "a gradient needs at least one stop");
+        let mut stops: Vec<_> = stops
+            .iter()
+            .map(|&(position, color)| (position.clamp(0.0, 1.0), color))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Gradient { stops }
+    }
+
+    /// Returns the gradient through `stops` exactly as given, e.g. stops typed in by a user, instead of sorting
+    /// and clamping them like `new()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::palettes::{Gradient, GradientError};
+    /// let black = [0x00, 0x00, 0x00, 0xFF];
+    /// let white = [0xFF, 0xFF, 0xFF, 0xFF];
+    /// let gradient = Gradient::from_stops(&[(0.0, black), (1.0, white)]).unwrap();
+    /// assert_eq!(gradient.colors(3)[1], [0x80, 0x80, 0x80, 0xFF]);
+    ///
+    /// assert_eq!(Gradient::from_stops(&[]), Err(GradientError::Empty));
+    /// assert_eq!(Gradient::from_stops(&[(1.5, white)]), Err(GradientError::OutOfRange));
+    /// assert_eq!(Gradient::from_stops(&[(1.0, white), (0.0, black)]), Err(GradientError::Unsorted));
+    /// ```
+    pub fn from_stops(stops: &[(f32, [u8; 4])]) -> Result<Gradient, GradientError> {
+        if stops.is_empty() {
+            return Err(GradientError::Empty);
+        }
+        if !stops
+            .iter()
+            .all(|&(position, _)| (0.0..=1.0).contains(&position))
+        {
+            return Err(GradientError::OutOfRange);
+        }
+        if stops.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+            return Err(GradientError::Unsorted);
+        }
+        Ok(Gradient {
+            stops: stops.to_vec(),
+        })
+    }
+
+    /// Returns the color at `position`, from 0.0 to 1.0.
+    pub fn at(&self, position: f32) -> [u8; 4] {
+        let next = self.stops.partition_point(|&(p, _)| p < position);
+        let (from, to) = match (next.checked_sub(1), self.stops.get(next)) {
+            (Some(i), Some(&to)) => (self.stops[i], to),
+            (Some(i), None) => return self.stops[i].1,
+            (None, _) => return self.stops[0].1,
+        };
+        let t = (position - from.0) / (to.0 - from.0);
+        let mut color = [0; 4];
+        for (c, (a, b)) in color.iter_mut().zip(from.1.iter().zip(&to.1)) {
+            *c = (*a as f32 + (*b as f32 - *a as f32) * t).round() as u8;
+        }
+        color
+    }
+
+    /// Returns `len` colors evenly spread over the gradient, coldest first.
+    pub fn colors(&self, len: usize) -> Vec<[u8; 4]> {
+        (0..len)
+            .map(|i| self.color(i, len.saturating_sub(1)))
+            .collect()
+    }
+}
+
+/// Errors returned by `Gradient::from_stops()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradientError {
+    /// There are no stops.
+    Empty,
+    /// A position isn't within 0.0 to 1.0.
+    OutOfRange,
+    /// The positions aren't in increasing order.
+    Unsorted,
+}
+
+impl fmt::Display for GradientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GradientError::Empty => write!(f, "a gradient needs at least one stop"),
+            GradientError::OutOfRange => write!(f, "gradient stops must be within 0.0 to 1.0"),
+            GradientError::Unsorted => write!(f, "gradient stops must be in increasing order"),
+        }
+    }
+}
+
+impl error::Error for GradientError {}
+
+impl Palette for Gradient {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        self.at(if max_heat == 0 {
+            0.0
+        } else {
+            heat as f32 / max_heat as f32
+        })
+    }
+}
+
+impl Palette for &Gradient {
+    fn color(&self, heat: usize, max_heat: usize) -> [u8; 4] {
+        (*self).color(heat, max_heat)
+    }
+}
+
+/// The names of all palettes accepted by `by_name()`.
+pub const NAMES: &[&str] = &[
+    "classic",
+    "blue_yellow",
+    "cividis",
+    "grayscale",
+    "blue_fire",
+    "green_fire",
+    "purple_fire",
+    "toxic",
+];
+
+/// Returns the palette with the given lower case name, e.g. `"classic"` or `"blue_yellow"`.
+/// # Examples
+/// ```
+/// # use doomfire::palettes;
+/// assert_eq!(palettes::by_name("cividis"), Some(palettes::CIVIDIS));
+/// assert_eq!(palettes::by_name("plaid"), None);
+/// ```
+pub fn by_name(name: &str) -> Option<[[u8; 4]; 37]> {
+    match name {
+        "classic" => Some(CLASSIC),
+        "blue_yellow" => Some(BLUE_YELLOW),
+        "cividis" => Some(CIVIDIS),
+        "grayscale" => Some(GRAYSCALE),
+        "blue_fire" => Some(BLUE_FIRE),
+        "green_fire" => Some(GREEN_FIRE),
+        "purple_fire" => Some(PURPLE_FIRE),
+        "toxic" => Some(TOXIC),
+        _ => None,
+    }
+}
+
+/// The classic Doom fire, the same as `PALETTE`.
+pub const CLASSIC: [[u8; 4]; 37] = crate::PALETTE;
+
+/// A blue to yellow fire for deuteranopia and protanopia (red-green color blindness).
+/// Only varies along the blue-yellow axis, which is seen by all but the rarest forms of color blindness.
+pub const BLUE_YELLOW: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x08, 0x0A, 0x11, 0xFF],
+    [0x0A, 0x0D, 0x1C, 0xFF],
+    [0x0B, 0x10, 0x26, 0xFF],
+    [0x0D, 0x13, 0x30, 0xFF],
+    [0x0E, 0x16, 0x3B, 0xFF],
+    [0x10, 0x19, 0x45, 0xFF],
+    [0x11, 0x1C, 0x50, 0xFF],
+    [0x13, 0x1F, 0x5A, 0xFF],
+    [0x14, 0x22, 0x64, 0xFF],
+    [0x16, 0x25, 0x6F, 0xFF],
+    [0x18, 0x29, 0x79, 0xFF],
+    [0x1C, 0x31, 0x84, 0xFF],
+    [0x21, 0x39, 0x8E, 0xFF],
+    [0x25, 0x41, 0x99, 0xFF],
+    [0x2A, 0x49, 0xA4, 0xFF],
+    [0x2E, 0x51, 0xAE, 0xFF],
+    [0x33, 0x59, 0xB9, 0xFF],
+    [0x37, 0x61, 0xC4, 0xFF],
+    [0x3B, 0x69, 0xCE, 0xFF],
+    [0x42, 0x71, 0xD5, 0xFF],
+    [0x52, 0x7A, 0xCE, 0xFF],
+    [0x61, 0x83, 0xC6, 0xFF],
+    [0x71, 0x8B, 0xBE, 0xFF],
+    [0x80, 0x94, 0xB6, 0xFF],
+    [0x90, 0x9D, 0xAF, 0xFF],
+    [0x9F, 0xA6, 0xA7, 0xFF],
+    [0xAF, 0xAF, 0x9F, 0xFF],
+    [0xBB, 0xB6, 0x8A, 0xFF],
+    [0xC7, 0xBE, 0x76, 0xFF],
+    [0xD3, 0xC5, 0x61, 0xFF],
+    [0xDE, 0xCD, 0x4C, 0xFF],
+    [0xEA, 0xD4, 0x37, 0xFF],
+    [0xF2, 0xDE, 0x52, 0xFF],
+    [0xF6, 0xE9, 0x8B, 0xFF],
+    [0xFB, 0xF4, 0xC5, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
+
+/// A fire following the cividis color map, which looks the same with and without color vision deficiency.
+pub const CIVIDIS: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x05, 0x0E, 0x1A, 0xFF],
+    [0x03, 0x15, 0x2E, 0xFF],
+    [0x01, 0x1C, 0x41, 0xFF],
+    [0x03, 0x22, 0x4E, 0xFF],
+    [0x0B, 0x28, 0x51, 0xFF],
+    [0x12, 0x2D, 0x53, 0xFF],
+    [0x1A, 0x33, 0x56, 0xFF],
+    [0x22, 0x39, 0x59, 0xFF],
+    [0x29, 0x3E, 0x5B, 0xFF],
+    [0x31, 0x44, 0x5E, 0xFF],
+    [0x39, 0x4A, 0x61, 0xFF],
+    [0x40, 0x4F, 0x63, 0xFF],
+    [0x48, 0x55, 0x66, 0xFF],
+    [0x50, 0x5A, 0x69, 0xFF],
+    [0x57, 0x60, 0x6B, 0xFF],
+    [0x5F, 0x66, 0x6E, 0xFF],
+    [0x67, 0x6B, 0x71, 0xFF],
+    [0x6E, 0x71, 0x73, 0xFF],
+    [0x76, 0x77, 0x76, 0xFF],
+    [0x7E, 0x7C, 0x77, 0xFF],
+    [0x86, 0x83, 0x74, 0xFF],
+    [0x8E, 0x8A, 0x71, 0xFF],
+    [0x96, 0x91, 0x6E, 0xFF],
+    [0x9E, 0x98, 0x6B, 0xFF],
+    [0xA6, 0x9F, 0x68, 0xFF],
+    [0xAE, 0xA5, 0x65, 0xFF],
+    [0xB6, 0xAC, 0x62, 0xFF],
+    [0xBE, 0xB3, 0x5F, 0xFF],
+    [0xC6, 0xBA, 0x5C, 0xFF],
+    [0xCE, 0xC1, 0x59, 0xFF],
+    [0xD7, 0xC8, 0x55, 0xFF],
+    [0xDF, 0xCF, 0x52, 0xFF],
+    [0xE7, 0xD5, 0x4F, 0xFF],
+    [0xEF, 0xDC, 0x4C, 0xFF],
+    [0xF7, 0xE3, 0x49, 0xFF],
+    [0xFF, 0xEA, 0x46, 0xFF],
+];
+
+/// A brightness only fire from black to white, for users who can't distinguish hues at all.
+pub const GRAYSCALE: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x0E, 0x0E, 0x0E, 0xFF],
+    [0x15, 0x15, 0x15, 0xFF],
+    [0x1C, 0x1C, 0x1C, 0xFF],
+    [0x23, 0x23, 0x23, 0xFF],
+    [0x29, 0x29, 0x29, 0xFF],
+    [0x30, 0x30, 0x30, 0xFF],
+    [0x37, 0x37, 0x37, 0xFF],
+    [0x3E, 0x3E, 0x3E, 0xFF],
+    [0x45, 0x45, 0x45, 0xFF],
+    [0x4C, 0x4C, 0x4C, 0xFF],
+    [0x53, 0x53, 0x53, 0xFF],
+    [0x5A, 0x5A, 0x5A, 0xFF],
+    [0x61, 0x61, 0x61, 0xFF],
+    [0x67, 0x67, 0x67, 0xFF],
+    [0x6E, 0x6E, 0x6E, 0xFF],
+    [0x75, 0x75, 0x75, 0xFF],
+    [0x7C, 0x7C, 0x7C, 0xFF],
+    [0x83, 0x83, 0x83, 0xFF],
+    [0x8A, 0x8A, 0x8A, 0xFF],
+    [0x91, 0x91, 0x91, 0xFF],
+    [0x98, 0x98, 0x98, 0xFF],
+    [0x9F, 0x9F, 0x9F, 0xFF],
+    [0xA5, 0xA5, 0xA5, 0xFF],
+    [0xAC, 0xAC, 0xAC, 0xFF],
+    [0xB3, 0xB3, 0xB3, 0xFF],
+    [0xBA, 0xBA, 0xBA, 0xFF],
+    [0xC1, 0xC1, 0xC1, 0xFF],
+    [0xC8, 0xC8, 0xC8, 0xFF],
+    [0xCF, 0xCF, 0xCF, 0xFF],
+    [0xD6, 0xD6, 0xD6, 0xFF],
+    [0xDD, 0xDD, 0xDD, 0xFF],
+    [0xE3, 0xE3, 0xE3, 0xFF],
+    [0xEA, 0xEA, 0xEA, 0xFF],
+    [0xF1, 0xF1, 0xF1, 0xFF],
+    [0xF8, 0xF8, 0xF8, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
+
+/// A blue gas flame, the classic fire with its red and blue swapped.
+pub const BLUE_FIRE: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x07, 0x07, 0x1F, 0xFF],
+    [0x07, 0x0F, 0x2F, 0xFF],
+    [0x07, 0x0F, 0x47, 0xFF],
+    [0x07, 0x17, 0x57, 0xFF],
+    [0x07, 0x1F, 0x67, 0xFF],
+    [0x07, 0x1F, 0x77, 0xFF],
+    [0x07, 0x27, 0x8F, 0xFF],
+    [0x07, 0x2F, 0x9F, 0xFF],
+    [0x07, 0x3F, 0xAF, 0xFF],
+    [0x07, 0x47, 0xBF, 0xFF],
+    [0x07, 0x47, 0xC7, 0xFF],
+    [0x07, 0x4F, 0xDF, 0xFF],
+    [0x07, 0x57, 0xDF, 0xFF],
+    [0x07, 0x57, 0xDF, 0xFF],
+    [0x07, 0x5F, 0xD7, 0xFF],
+    [0x07, 0x5F, 0xD7, 0xFF],
+    [0x0F, 0x67, 0xD7, 0xFF],
+    [0x0F, 0x6F, 0xCF, 0xFF],
+    [0x0F, 0x77, 0xCF, 0xFF],
+    [0x0F, 0x7F, 0xCF, 0xFF],
+    [0x17, 0x87, 0xCF, 0xFF],
+    [0x17, 0x87, 0xC7, 0xFF],
+    [0x17, 0x8F, 0xC7, 0xFF],
+    [0x1F, 0x97, 0xC7, 0xFF],
+    [0x1F, 0x9F, 0xBF, 0xFF],
+    [0x1F, 0x9F, 0xBF, 0xFF],
+    [0x27, 0xA7, 0xBF, 0xFF],
+    [0x27, 0xA7, 0xBF, 0xFF],
+    [0x2F, 0xAF, 0xBF, 0xFF],
+    [0x2F, 0xAF, 0xB7, 0xFF],
+    [0x2F, 0xB7, 0xB7, 0xFF],
+    [0x37, 0xB7, 0xB7, 0xFF],
+    [0x6F, 0xCF, 0xCF, 0xFF],
+    [0x9F, 0xDF, 0xDF, 0xFF],
+    [0xC7, 0xEF, 0xEF, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
+
+/// A green witch fire, from deep green to white.
+pub const GREEN_FIRE: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x07, 0x1F, 0x07, 0xFF],
+    [0x07, 0x2F, 0x07, 0xFF],
+    [0x07, 0x47, 0x07, 0xFF],
+    [0x07, 0x57, 0x07, 0xFF],
+    [0x07, 0x67, 0x07, 0xFF],
+    [0x07, 0x77, 0x07, 0xFF],
+    [0x07, 0x8F, 0x07, 0xFF],
+    [0x07, 0x9F, 0x07, 0xFF],
+    [0x07, 0xAF, 0x07, 0xFF],
+    [0x07, 0xBF, 0x07, 0xFF],
+    [0x07, 0xC7, 0x07, 0xFF],
+    [0x07, 0xDF, 0x07, 0xFF],
+    [0x07, 0xDF, 0x07, 0xFF],
+    [0x07, 0xDF, 0x07, 0xFF],
+    [0x07, 0xD7, 0x07, 0xFF],
+    [0x07, 0xD7, 0x07, 0xFF],
+    [0x0F, 0xD7, 0x0F, 0xFF],
+    [0x0F, 0xCF, 0x0F, 0xFF],
+    [0x0F, 0xCF, 0x0F, 0xFF],
+    [0x0F, 0xCF, 0x0F, 0xFF],
+    [0x17, 0xCF, 0x17, 0xFF],
+    [0x17, 0xC7, 0x17, 0xFF],
+    [0x17, 0xC7, 0x17, 0xFF],
+    [0x1F, 0xC7, 0x1F, 0xFF],
+    [0x1F, 0xBF, 0x1F, 0xFF],
+    [0x1F, 0xBF, 0x1F, 0xFF],
+    [0x27, 0xBF, 0x27, 0xFF],
+    [0x27, 0xBF, 0x27, 0xFF],
+    [0x2F, 0xBF, 0x2F, 0xFF],
+    [0x2F, 0xB7, 0x2F, 0xFF],
+    [0x2F, 0xB7, 0x2F, 0xFF],
+    [0x37, 0xB7, 0x37, 0xFF],
+    [0x6F, 0xCF, 0x6F, 0xFF],
+    [0x9F, 0xDF, 0x9F, 0xFF],
+    [0xC7, 0xEF, 0xC7, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
+
+/// A purple fire burning to magenta and white.
+pub const PURPLE_FIRE: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x07, 0x07, 0x1F, 0xFF],
+    [0x0F, 0x07, 0x2F, 0xFF],
+    [0x0F, 0x07, 0x47, 0xFF],
+    [0x17, 0x07, 0x57, 0xFF],
+    [0x1F, 0x07, 0x67, 0xFF],
+    [0x1F, 0x07, 0x77, 0xFF],
+    [0x27, 0x07, 0x8F, 0xFF],
+    [0x2F, 0x07, 0x9F, 0xFF],
+    [0x3F, 0x07, 0xAF, 0xFF],
+    [0x47, 0x07, 0xBF, 0xFF],
+    [0x47, 0x07, 0xC7, 0xFF],
+    [0x4F, 0x07, 0xDF, 0xFF],
+    [0x57, 0x07, 0xDF, 0xFF],
+    [0x57, 0x07, 0xDF, 0xFF],
+    [0x5F, 0x07, 0xD7, 0xFF],
+    [0x5F, 0x07, 0xD7, 0xFF],
+    [0x67, 0x0F, 0xD7, 0xFF],
+    [0x6F, 0x0F, 0xCF, 0xFF],
+    [0x77, 0x0F, 0xCF, 0xFF],
+    [0x7F, 0x0F, 0xCF, 0xFF],
+    [0x87, 0x17, 0xCF, 0xFF],
+    [0x87, 0x17, 0xC7, 0xFF],
+    [0x8F, 0x17, 0xC7, 0xFF],
+    [0x97, 0x1F, 0xC7, 0xFF],
+    [0x9F, 0x1F, 0xBF, 0xFF],
+    [0x9F, 0x1F, 0xBF, 0xFF],
+    [0xA7, 0x27, 0xBF, 0xFF],
+    [0xA7, 0x27, 0xBF, 0xFF],
+    [0xAF, 0x2F, 0xBF, 0xFF],
+    [0xAF, 0x2F, 0xB7, 0xFF],
+    [0xB7, 0x2F, 0xB7, 0xFF],
+    [0xB7, 0x37, 0xB7, 0xFF],
+    [0xCF, 0x6F, 0xCF, 0xFF],
+    [0xDF, 0x9F, 0xDF, 0xFF],
+    [0xEF, 0xC7, 0xEF, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
+
+/// A radioactive acid green to yellow fire.
+pub const TOXIC: [[u8; 4]; 37] = [
+    [0x07, 0x07, 0x07, 0xFF],
+    [0x07, 0x1F, 0x07, 0xFF],
+    [0x0F, 0x2F, 0x07, 0xFF],
+    [0x0F, 0x47, 0x07, 0xFF],
+    [0x17, 0x57, 0x07, 0xFF],
+    [0x1F, 0x67, 0x07, 0xFF],
+    [0x1F, 0x77, 0x07, 0xFF],
+    [0x27, 0x8F, 0x07, 0xFF],
+    [0x2F, 0x9F, 0x07, 0xFF],
+    [0x3F, 0xAF, 0x07, 0xFF],
+    [0x47, 0xBF, 0x07, 0xFF],
+    [0x47, 0xC7, 0x07, 0xFF],
+    [0x4F, 0xDF, 0x07, 0xFF],
+    [0x57, 0xDF, 0x07, 0xFF],
+    [0x57, 0xDF, 0x07, 0xFF],
+    [0x5F, 0xD7, 0x07, 0xFF],
+    [0x5F, 0xD7, 0x07, 0xFF],
+    [0x67, 0xD7, 0x0F, 0xFF],
+    [0x6F, 0xCF, 0x0F, 0xFF],
+    [0x77, 0xCF, 0x0F, 0xFF],
+    [0x7F, 0xCF, 0x0F, 0xFF],
+    [0x87, 0xCF, 0x17, 0xFF],
+    [0x87, 0xC7, 0x17, 0xFF],
+    [0x8F, 0xC7, 0x17, 0xFF],
+    [0x97, 0xC7, 0x1F, 0xFF],
+    [0x9F, 0xBF, 0x1F, 0xFF],
+    [0x9F, 0xBF, 0x1F, 0xFF],
+    [0xA7, 0xBF, 0x27, 0xFF],
+    [0xA7, 0xBF, 0x27, 0xFF],
+    [0xAF, 0xBF, 0x2F, 0xFF],
+    [0xAF, 0xB7, 0x2F, 0xFF],
+    [0xB7, 0xB7, 0x2F, 0xFF],
+    [0xB7, 0xB7, 0x37, 0xFF],
+    [0xCF, 0xCF, 0x6F, 0xFF],
+    [0xDF, 0xDF, 0x9F, 0xFF],
+    [0xEF, 0xEF, 0xC7, 0xFF],
+    [0xFF, 0xFF, 0xFF, 0xFF],
+];
@@ -0,0 +1,365 @@
+//! Post processing passes applied to the rendered rgba frame by `Doomfire::draw()`.
+//!
+//! Passes are added to the `PostFx` pipeline of a fire, see `Doomfire::postfx_mut()`.
+use crate::Doomfire;
+use alloc::{boxed::Box, vec::Vec};
+use core::cell::RefCell;
+
+/// Converts a 0.0 to 1.0 strength into a weight out of 256 for cheap integer blending.
+fn weight(strength: f32) -> u32 {
+    (strength.clamp(0.0, 1.0) * 256.0) as u32
+}
+
+/// A retro CRT look: darkened scanlines, a slight horizontal blur and optionally rounded, curved looking corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crt {
+    /// How much every other row is darkened, from 0.0 (off) to 1.0 (black).
+    pub scanlines: f32,
+    /// How much every pixel is mixed with its left and right neighbours, from 0.0 (off) to 1.0.
+    pub blur: f32,
+    /// How much the corners are rounded off to hint at a curved screen, from 0.0 (off) to 1.0.
+    pub barrel: f32,
+}
+
+/// Returns half strength scanlines, a light blur and no barrel.
+impl Default for Crt {
+    fn default() -> Self {
+        Crt {
+            scanlines: 0.5,
+            blur: 0.3,
+            barrel: 0.0,
+        }
+    }
+}
+
+impl Crt {
+    /// Applies the effect to a `width` x `height` rgba frame.
+    pub fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let blur = weight(self.blur);
+        let scanline = 256 - weight(self.scanlines);
+
+        for (y, row) in frame.chunks_exact_mut(width * 4).take(height).enumerate() {
+            if blur > 0 {
+                // The left neighbour is already blurred so keep its original value around.
+                let mut previous = [row[0], row[1], row[2]];
+                for x in 0..width {
+                    let i = x * 4;
+                    let next = if x + 1 < width { i + 4 } else { i };
+                    for c in 0..3 {
+                        let current = row[i + c];
+                        let neighbours = (previous[c] as u32 + row[next + c] as u32) / 2;
+                        row[i + c] =
+                            ((current as u32 * (256 - blur) + neighbours * blur) >> 8) as u8;
+                        previous[c] = current;
+                    }
+                }
+            }
+            if y % 2 == 1 && scanline < 256 {
+                for pixel in row.chunks_exact_mut(4) {
+                    for c in pixel.iter_mut().take(3) {
+                        *c = ((*c as u32 * scanline) >> 8) as u8;
+                    }
+                }
+            }
+        }
+
+        if self.barrel > 0.0 {
+            self.round_corners(frame, width, height);
+        }
+    }
+
+    /// Blacks out the pixels outside of circles in the corners of the frame.
+    fn round_corners(&self, frame: &mut [u8], width: usize, height: usize) {
+        let radius = (self.barrel.min(1.0) * width.min(height) as f32 / 4.0) as usize;
+        let r2 = radius * radius;
+        for dy in 0..radius {
+            for dx in 0..radius {
+                // Distance from the center of the corner circle.
+                let (cx, cy) = (radius - dx, radius - dy);
+                if cx * cx + cy * cy <= r2 {
+                    continue;
+                }
+                for &(x, y) in &[
+                    (dx, dy),
+                    (width - 1 - dx, dy),
+                    (dx, height - 1 - dy),
+                    (width - 1 - dx, height - 1 - dy),
+                ] {
+                    let i = (y * width + x) * 4;
+                    frame[i..i + 3].copy_from_slice(&[0, 0, 0]);
+                }
+            }
+        }
+    }
+}
+
+/// Makes the brightest parts of the fire glow by blurring them and adding them back on top of the frame.
+#[derive(Debug, Clone)]
+pub struct Bloom {
+    threshold: u32,
+    radius: usize,
+    strength: u32,
+    // The bright pass and a buffer for the separable blur, rgb channels only.
+    // Kept between frames so drawing doesn't allocate.
+    scratch: RefCell<(Vec<u16>, Vec<u16>)>,
+}
+
+impl Bloom {
+    /// Returns a bloom pass where pixels brighter than `threshold` (0 to 255 luminance) glow over `radius` pixels.
+    /// `strength` scales the glow, 1.0 adds it at full brightness.
+    /// # Examples
+    /// ```
+    /// # use doomfire::postfx::Bloom;
+    /// let bloom = Bloom::new(180, 6, 0.8);
+    /// ```
+    pub fn new(threshold: u8, radius: usize, strength: f32) -> Bloom {
+        Bloom {
+            threshold: threshold as u32,
+            radius,
+            strength: (strength.max(0.0) * 256.0) as u32,
+            scratch: RefCell::new((Vec::new(), Vec::new())),
+        }
+    }
+
+    /// Applies the effect to a `width` x `height` rgba frame.
+    pub fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        let mut scratch = self.scratch.borrow_mut();
+        let (bright, blurred) = &mut *scratch;
+        bright.resize(width * height * 3, 0);
+        blurred.resize(width * height * 3, 0);
+
+        // Keep only what's above the threshold, fading in so there is no hard edge.
+        let range = 256 - self.threshold.min(255);
+        for (pixel, out) in frame.chunks_exact(4).zip(bright.chunks_exact_mut(3)) {
+            let luminance =
+                (pixel[0] as u32 * 77 + pixel[1] as u32 * 150 + pixel[2] as u32 * 29) >> 8;
+            let amount = luminance.saturating_sub(self.threshold);
+            for (o, &c) in out.iter_mut().zip(pixel.iter()) {
+                *o = (c as u32 * amount / range) as u16;
+            }
+        }
+
+        // A horizontal and a vertical box blur are the same as (but a lot cheaper than) a square one.
+        box_blur(bright, blurred, width, height, 3, 3 * width, self.radius);
+        box_blur(blurred, bright, height, width, 3 * width, 3, self.radius);
+
+        for (pixel, glow) in frame.chunks_exact_mut(4).zip(bright.chunks_exact(3)) {
+            for (c, &g) in pixel.iter_mut().zip(glow.iter()) {
+                *c = (*c as u32 + ((g as u32 * self.strength) >> 8)).min(255) as u8;
+            }
+        }
+    }
+}
+
+/// Box blurs `lines` lines of `len` rgb pixels from `src` into `dst` with a running sum.
+/// `step` is the distance between two pixels of a line and `stride` between two lines, so this blurs both rows and columns.
+fn box_blur(
+    src: &[u16],
+    dst: &mut [u16],
+    len: usize,
+    lines: usize,
+    step: usize,
+    stride: usize,
+    radius: usize,
+) {
+    if len == 0 {
+        return;
+    }
+    let diameter = (2 * radius + 1) as u32;
+    for line in 0..lines {
+        let start = line * stride;
+        let at = |i: usize, c: usize| src[start + i * step + c] as u32;
+        for c in 0..3 {
+            // Pixels outside of the frame count as black.
+            let mut sum: u32 = (0..=radius.min(len - 1)).map(|i| at(i, c)).sum();
+            for i in 0..len {
+                dst[start + i * step + c] = (sum / diameter) as u16;
+                if i + radius + 1 < len {
+                    sum += at(i + radius + 1, c);
+                }
+                if i >= radius {
+                    sum -= at(i - radius, c);
+                }
+            }
+        }
+    }
+}
+
+/// Darkens the frame towards the edges, e.g. for a fullscreen menu background.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vignette {
+    /// How dark the corners furthest from the center get, from 0.0 (off) to 1.0 (black).
+    pub strength: f32,
+    /// The least darkened point, as fractions of the width and height. `(0.5, 0.5)` is the middle of the frame.
+    pub center: (f32, f32),
+}
+
+/// Returns a half strength vignette centered on the frame.
+impl Default for Vignette {
+    fn default() -> Self {
+        Vignette {
+            strength: 0.5,
+            center: (0.5, 0.5),
+        }
+    }
+}
+
+impl Vignette {
+    /// Applies the effect to a `width` x `height` rgba frame.
+    pub fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        let (cx, cy) = self.center;
+        // Normalize by the furthest corner so only that corner gets the full strength.
+        let max_dx = cx.max(1.0 - cx);
+        let max_dy = cy.max(1.0 - cy);
+        let max_distance = max_dx * max_dx + max_dy * max_dy;
+        let strength = self.strength.clamp(0.0, 1.0) / max_distance;
+
+        for (y, row) in frame.chunks_exact_mut(width * 4).take(height).enumerate() {
+            let dy = (y as f32 + 0.5) / height as f32 - cy;
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let dx = (x as f32 + 0.5) / width as f32 - cx;
+                let factor = weight(1.0 - strength * (dx * dx + dy * dy));
+                for c in pixel.iter_mut().take(3) {
+                    *c = ((*c as u32 * factor) >> 8) as u8;
+                }
+            }
+        }
+    }
+}
+
+/// A post processing pass, implemented by `Crt`, `Bloom`, `Vignette` and closures taking the same arguments.
+/// Passes are `Send` so fires can move between threads.
+pub trait Pass: Send {
+    /// Applies the pass to a `width` x `height` rgba frame.
+    fn apply(&self, frame: &mut [u8], width: usize, height: usize);
+
+    /// Returns the number of bytes the pass takes, including buffers it keeps between frames.
+    fn memory_usage(&self) -> usize {
+        core::mem::size_of_val(self)
+    }
+}
+
+impl Pass for Crt {
+    fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        Crt::apply(self, frame, width, height)
+    }
+}
+
+impl Pass for Bloom {
+    fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        Bloom::apply(self, frame, width, height)
+    }
+
+    fn memory_usage(&self) -> usize {
+        let scratch = self.scratch.borrow();
+        core::mem::size_of_val(self) + (scratch.0.capacity() + scratch.1.capacity()) * 2
+    }
+}
+
+impl Pass for Vignette {
+    fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        Vignette::apply(self, frame, width, height)
+    }
+}
+
+impl<F: Fn(&mut [u8], usize, usize) + Send> Pass for F {
+    fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        self(frame, width, height)
+    }
+}
+
+/// A list of passes that run in order on the frame rendered by `Doomfire::draw()`.
+/// # Examples
+/// ```
+/// # use doomfire::postfx::{Bloom, Crt, PostFx, Vignette};
+/// let mut postfx = PostFx::new();
+/// postfx
+///     .push(Bloom::new(180, 6, 0.8))
+///     .push(Vignette::default())
+///     // Custom passes are closures, this one makes the frame transparent.
+///     .push(|frame: &mut [u8], _width, _height| {
+///         frame.chunks_exact_mut(4).for_each(|pixel| pixel[3] = 0x80)
+///     })
+///     .push(Crt::default());
+/// ```
+#[derive(Default)]
+pub struct PostFx {
+    passes: Vec<Box<dyn Pass>>,
+}
+
+impl PostFx {
+    /// Returns an empty pipeline.
+    pub fn new() -> PostFx {
+        PostFx::default()
+    }
+
+    /// Adds a pass that runs after the passes added before it.
+    pub fn push<P: Pass + 'static>(&mut self, pass: P) -> &mut PostFx {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Removes all passes.
+    pub fn clear(&mut self) {
+        self.passes.clear();
+    }
+
+    /// Returns the number of passes.
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Returns whether there are no passes.
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Returns the number of bytes the passes take, see `Pass::memory_usage()`.
+    pub fn memory_usage(&self) -> usize {
+        self.passes.capacity() * core::mem::size_of::<Box<dyn Pass>>()
+            + self
+                .passes
+                .iter()
+                .map(|pass| pass.memory_usage())
+                .sum::<usize>()
+    }
+
+    /// Runs all passes in order on a `width` x `height` rgba frame.
+    pub fn apply(&self, frame: &mut [u8], width: usize, height: usize) {
+        for pass in &self.passes {
+            pass.apply(frame, width, height);
+        }
+    }
+}
+
+impl Doomfire {
+    /// Replaces the post processing passes that run at the end of `draw()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{postfx::{Crt, PostFx}, Doomfire};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// let mut postfx = PostFx::new();
+    /// postfx.push(Crt {
+    ///     barrel: 0.2,
+    ///     ..Crt::default()
+    /// });
+    /// doomfire.set_postfx(postfx);
+    /// ```
+    pub fn set_postfx(&mut self, postfx: PostFx) {
+        self.postfx = postfx;
+    }
+
+    /// Returns the post processing passes to add or remove passes in place.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{postfx::Vignette, Doomfire};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.postfx_mut().push(Vignette::default());
+    /// ```
+    pub fn postfx_mut(&mut self) -> &mut PostFx {
+        &mut self.postfx
+    }
+}
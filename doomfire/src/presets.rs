@@ -0,0 +1,134 @@
+//! Named `FireConfig` presets loaded from RON files at runtime.
+//!
+//! Every `.ron` file holds a single `FireConfig` and is named after its file stem, so `presets/ice.ron` becomes `"ice"`.
+//! Calling `reload_changed()` every now and then picks up edited files, letting artists tweak a running fire
+//! without recompiling the host application.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{presets::PresetRegistry, Doomfire};
+//!
+//! let mut presets = PresetRegistry::new();
+//! presets.load_dir("presets").unwrap();
+//!
+//! let mut doomfire = Doomfire::new(600, 400);
+//! presets.apply("ice", &mut doomfire).unwrap();
+//!
+//! // In your render loop.
+//! for name in presets.reload_changed().unwrap() {
+//!     if name == "ice" {
+//!         presets.apply("ice", &mut doomfire).unwrap();
+//!     }
+//! }
+//! ```
+use crate::{config::ConfigError, Doomfire, FireConfig};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+struct Preset {
+    config: FireConfig,
+    // Where the preset was loaded from and when that file was last modified.
+    source: Option<(PathBuf, Option<SystemTime>)>,
+}
+
+/// A collection of named presets.
+#[derive(Default)]
+pub struct PresetRegistry {
+    presets: HashMap<String, Preset>,
+}
+
+impl PresetRegistry {
+    /// Returns an empty registry.
+    pub fn new() -> PresetRegistry {
+        PresetRegistry::default()
+    }
+
+    /// Adds (or replaces) a preset that doesn't come from a file.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{presets::PresetRegistry, FireConfig};
+    /// let mut presets = PresetRegistry::new();
+    /// presets.insert("wide", FireConfig { width: 1200, ..FireConfig::default() });
+    /// assert_eq!(presets.get("wide").unwrap().width, 1200);
+    /// ```
+    pub fn insert<S: Into<String>>(&mut self, name: S, config: FireConfig) {
+        self.presets.insert(
+            name.into(),
+            Preset {
+                config,
+                source: None,
+            },
+        );
+    }
+
+    /// Loads a RON file as a preset named after the file stem and returns that name.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<String, ConfigError> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        let modified = fs::metadata(path)?.modified().ok();
+        let config = FireConfig::from_ron_str(&fs::read_to_string(path)?)?;
+
+        self.presets.insert(
+            name.clone(),
+            Preset {
+                config,
+                source: Some((path.to_path_buf(), modified)),
+            },
+        );
+        Ok(name)
+    }
+
+    /// Loads every `.ron` file in a directory and returns how many presets were loaded.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize, ConfigError> {
+        let mut count = 0;
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "ron") {
+                self.load_file(&path)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Reloads the presets whose file changed since it was loaded and returns their names.
+    pub fn reload_changed(&mut self) -> Result<Vec<String>, ConfigError> {
+        let mut changed = Vec::new();
+        for (name, preset) in self.presets.iter_mut() {
+            if let Some((path, modified)) = &mut preset.source {
+                let now = fs::metadata(&*path)?.modified().ok();
+                if now != *modified {
+                    preset.config = FireConfig::from_ron_str(&fs::read_to_string(&*path)?)?;
+                    *modified = now;
+                    changed.push(name.clone());
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Returns the preset with the given name.
+    pub fn get(&self, name: &str) -> Option<&FireConfig> {
+        self.presets.get(name).map(|p| &p.config)
+    }
+
+    /// Returns the names of all presets in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.keys().map(|k| k.as_str())
+    }
+
+    /// Applies a preset to a running fire, see `FireConfig::apply()`.
+    pub fn apply(&self, name: &str, fire: &mut Doomfire) -> Result<(), ConfigError> {
+        self.get(name)
+            .ok_or_else(|| ConfigError::UnknownPreset(name.to_string()))?
+            .apply(fire)
+    }
+}
@@ -0,0 +1,134 @@
+//! Droplets falling from the top that quench the flames where they land, a livelier way for a fire to go out.
+//!
+//! # Example
+//! ```
+//! use doomfire::{Doomfire, Rain};
+//!
+//! let mut doomfire = Doomfire::from_seed(600, 400, 1);
+//! doomfire.ignite();
+//! doomfire.set_rain(Some(Rain { rate: 4.0, ..Rain::default() }));
+//! doomfire.extinguish();
+//! for _ in 0..100 {
+//!     doomfire.update();
+//! }
+//! ```
+use crate::emitter::segment_cells;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::Float;
+use crate::Doomfire;
+use rand::Rng;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How hard and how fast it rains on a fire, see `Doomfire::set_rain()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Rain {
+    /// The average number of droplets starting at the top every update, at most one for every column.
+    pub rate: f32,
+    /// The heat removed where a droplet lands.
+    pub strength: usize,
+    /// The radius in pixels of the splash of a droplet.
+    pub radius: f32,
+    /// The number of rows a droplet falls every update.
+    pub speed: f32,
+}
+
+/// A light shower.
+impl Default for Rain {
+    fn default() -> Self {
+        Rain {
+            rate: 1.0,
+            strength: 12,
+            radius: 2.0,
+            speed: 4.0,
+        }
+    }
+}
+
+impl Doomfire {
+    /// Returns the rain falling on the fire, see `set_rain()`.
+    pub fn rain(&self) -> Option<Rain> {
+        self.rain
+    }
+
+    /// Lets droplets fall from the top, every droplet lands on the first burning pixel it meets and
+    /// removes `strength` heat in a splash around it. Droplets that meet no flames vanish at the bottom.
+    /// The bottom row of a lit fire keeps burning, extinguish the fire to let the rain put it out.
+    /// Pass `None` (the default) to stop the rain, droplets still falling vanish.
+    /// # Examples
+    /// ```
+    /// # use doomfire::{Doomfire, Rain};
+    /// let mut doomfire = Doomfire::from_seed(600, 400, 1);
+    /// doomfire.set_rain(Some(Rain { rate: 0.5, strength: 36, ..Rain::default() }));
+    /// ```
+    pub fn set_rain(&mut self, rain: Option<Rain>) {
+        self.droplets.clear();
+        self.rain = rain;
+        self.reserve_droplets();
+    }
+
+    /// Makes room for every droplet that can be falling at once, so updates don't allocate.
+    pub(crate) fn reserve_droplets(&mut self) {
+        if let Some(rain) = self.rain {
+            // Float to integer casts saturate, so the fastest droplets live for a single update.
+            let falling = self.height / rain.speed.max(1.0) as usize + 1;
+            let room = (rate(&rain, self.width).ceil() as usize).saturating_mul(falling);
+            self.droplets
+                .reserve(room.saturating_sub(self.droplets.len()));
+        }
+    }
+
+    /// Moves the droplets down, splashing the ones that land, and lets new ones start at the top.
+    pub(crate) fn apply_rain(&mut self) {
+        let rain = match self.rain {
+            Some(rain) if self.width > 0 && self.height > 0 => rain,
+            _ => return,
+        };
+        // The bottom row of a lit fire is its fuel, don't drown it.
+        let floor = self.height - self.is_lit as usize;
+
+        let mut i = 0;
+        while i < self.droplets.len() {
+            let (x, y) = self.droplets[i];
+            let to = (y + rain.speed.max(1.0)).min(self.height as f32);
+            let landed =
+                (y as usize..to as usize).find(|&y| self.fire_pixels[y * self.width + x] > 0);
+            match landed {
+                Some(y) => {
+                    let center = (x as f32, y as f32);
+                    let size = (self.width, floor);
+                    segment_cells(center, center, rain.radius.max(0.5), size, |x, y| {
+                        let pixel = &mut self.fire_pixels[y * self.width + x];
+                        *pixel = (*pixel as usize).saturating_sub(rain.strength) as u8;
+                    });
+                    self.droplets.swap_remove(i);
+                }
+                None if to >= self.height as f32 => {
+                    self.droplets.swap_remove(i);
+                }
+                None => {
+                    self.droplets[i].1 = to;
+                    i += 1;
+                }
+            }
+        }
+
+        let rate = rate(&rain, self.width);
+        let mut count = rate as usize;
+        if self.rng.gen::<f32>() < rate.fract() {
+            count += 1;
+        }
+        for _ in 0..count.min(self.droplets.capacity() - self.droplets.len()) {
+            let x = self.rng.gen_range(0, self.width);
+            self.droplets.push((x, 0.0));
+        }
+    }
+}
+
+/// Returns the droplets starting every update, `rain.rate` clamped to `0.0..=width`.
+fn rate(rain: &Rain, width: usize) -> f32 {
+    rain.rate.max(0.0).min(width as f32)
+}
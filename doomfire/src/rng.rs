@@ -0,0 +1,42 @@
+//! Where a fire draws its random numbers from, see `Doomfire::with_rng()`.
+use alloc::boxed::Box;
+use rand::{Error, RngCore};
+use rand_pcg::Pcg64Mcg;
+
+/// The built-in generator, or any other one.
+/// The kernel is stepped with the generator itself rather than through this enum, so the built-in generator
+/// stays inlined in the hot loop and only other generators pay for dynamic dispatch.
+pub(crate) enum FireRng {
+    Pcg(Pcg64Mcg),
+    Custom(Box<dyn RngCore + Send>),
+}
+
+impl RngCore for FireRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            FireRng::Pcg(rng) => rng.next_u32(),
+            FireRng::Custom(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            FireRng::Pcg(rng) => rng.next_u64(),
+            FireRng::Custom(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            FireRng::Pcg(rng) => rng.fill_bytes(dest),
+            FireRng::Custom(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        match self {
+            FireRng::Pcg(rng) => rng.try_fill_bytes(dest),
+            FireRng::Custom(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Animating a fire with a [rhai](https://rhai.rs) script that runs every frame,
+//! so choreographed fires can be tweaked without recompiling.
+//!
+//! The script sees the variables `frame` (the number of times it ran before), `time` (as passed to `Script::run()`),
+//! `width` and `height`, and can call:
+//! - `ignite()` and `extinguish()`,
+//! - `intensity(value)` and `wind(value)`, see `Doomfire::set_intensity()` and `Doomfire::set_wind()`,
+//! - `palette(name)` with the name of a palette in `palettes`,
+//! - `heat(x, y, heat)` and `heat_circle(x, y, radius, heat)` to add (or remove) heat.
+//!
+//! Variables don't outlive a frame, derive what changes over time from `frame` and `time` instead.
+//!
+//! # Example
+//! ```
+//! use doomfire::{script::Script, Doomfire};
+//!
+//! let mut script = Script::new(r#"
+//!     // Gusts of wind that come and go, and a flare in the middle every second.
+//!     wind(sin(time) * 0.5);
+//!     if frame % 60 == 0 {
+//!         heat_circle(width / 2, height - 10, 8.0, 36);
+//!     }
+//! "#).unwrap();
+//!
+//! let mut doomfire = Doomfire::new(600, 400);
+//! doomfire.ignite();
+//! for frame in 0..120 {
+//!     script.run(&mut doomfire, frame as f32 / 60.0).unwrap();
+//!     doomfire.update();
+//! }
+//! ```
+use crate::{config::ConfigError, control::Command, Doomfire};
+use rhai::{Engine, EvalAltResult, ParseError, Scope, AST, FLOAT, INT};
+use std::{cell::RefCell, error, fmt, rc::Rc};
+
+/// Errors returned while compiling or running a script.
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The script doesn't compile.
+    Parse(ParseError),
+    /// The script failed while running.
+    Eval(Box<EvalAltResult>),
+    /// The script asked for something the fire can't do, e.g. an unknown palette.
+    Command(ConfigError),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScriptError::Parse(e) => write!(f, "invalid script: {}", e),
+            ScriptError::Eval(e) => write!(f, "script failed: {}", e),
+            ScriptError::Command(e) => write!(f, "script failed: {}", e),
+        }
+    }
+}
+
+impl error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ScriptError::Parse(e) => Some(e),
+            ScriptError::Eval(e) => Some(e.as_ref()),
+            ScriptError::Command(e) => Some(e),
+        }
+    }
+}
+
+/// A compiled script, run once per frame with `run()`.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    frame: INT,
+    // The commands called by the running script, applied to the fire once it's done.
+    commands: Rc<RefCell<Vec<Command>>>,
+}
+
+impl Script {
+    /// Compiles `source`.
+    pub fn new(source: &str) -> Result<Script, ScriptError> {
+        let commands = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new();
+
+        let push = |commands: &Rc<RefCell<Vec<Command>>>| {
+            let commands = commands.clone();
+            move |command| commands.borrow_mut().push(command)
+        };
+        let p = push(&commands);
+        engine.register_fn("ignite", move || p(Command::Ignite));
+        let p = push(&commands);
+        engine.register_fn("extinguish", move || p(Command::Extinguish));
+        let p = push(&commands);
+        engine.register_fn("intensity", move |v: FLOAT| p(Command::Intensity(v as f32)));
+        let p = push(&commands);
+        engine.register_fn("wind", move |v: FLOAT| p(Command::Wind(v as f32)));
+        let p = push(&commands);
+        engine.register_fn("palette", move |name: &str| {
+            p(Command::Palette(name.to_string()))
+        });
+        let p = push(&commands);
+        engine.register_fn("heat", move |x: INT, y: INT, heat: INT| {
+            if x >= 0 && y >= 0 {
+                p(Command::Heat {
+                    x: x as usize,
+                    y: y as usize,
+                    heat: heat.max(0) as usize,
+                })
+            }
+        });
+        let p = push(&commands);
+        engine.register_fn(
+            "heat_circle",
+            move |x: INT, y: INT, radius: FLOAT, heat: INT| {
+                p(Command::HeatCircle {
+                    center: (x as f32, y as f32),
+                    radius: radius as f32,
+                    heat: heat.max(0) as usize,
+                })
+            },
+        );
+
+        let ast = engine.compile(source).map_err(ScriptError::Parse)?;
+        Ok(Script {
+            engine,
+            ast,
+            scope: Scope::new(),
+            frame: 0,
+            commands,
+        })
+    }
+
+    /// Runs the script for the current frame of `fire` and applies what it called, in order.
+    pub fn run(&mut self, fire: &mut Doomfire, time: f32) -> Result<(), ScriptError> {
+        self.scope.set_value("frame", self.frame);
+        self.scope.set_value("time", time as FLOAT);
+        self.scope.set_value("width", fire.width() as INT);
+        self.scope.set_value("height", fire.height() as INT);
+        self.frame += 1;
+
+        let variables = self.scope.len();
+        let result = self
+            .engine
+            .run_ast_with_scope(&mut self.scope, &self.ast)
+            .map_err(ScriptError::Eval);
+        // Drop the variables declared by the script, or they would pile up every frame.
+        self.scope.rewind(variables);
+        // Apply whatever ran before a failure, and don't carry it over to the next frame.
+        for command in self.commands.borrow_mut().drain(..) {
+            fire.apply_command(&command).map_err(ScriptError::Command)?;
+        }
+        result
+    }
+}
@@ -0,0 +1,172 @@
+//! A vectorized `update()` for the common settings, see `Doomfire::set_simd()`.
+//!
+//! Like `kernel::step()` the fire is stepped a row at a time from the bottom up, but 16 pixels of a row at once.
+//! Their draws come from 16 bytes of a wyrand generator instead of one by one through `rand`, and are turned into
+//! drift and cooling without branches, which the compiler turns into vector instructions. Only moving the heat up
+//! stays a pixel at a time.
+//!
+//! As all heat climbs a single row, all a row's pixels read is the row as it was before the step,
+//! except where heat drifting a column to the right landed, which the kernel steps again.
+use crate::kernel::{self, Heat, Params};
+use crate::{Distribution, Doomfire};
+use rand::Rng;
+
+/// The number of pixels stepped at once.
+const LANES: usize = 16;
+
+/// wyrand, a generator of a single multiplication per 8 random bytes.
+struct WyRand(u64);
+
+impl WyRand {
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0xa076_1d64_78bd_642f);
+        let t = (self.0 as u128).wrapping_mul((self.0 ^ 0xe703_7ed1_a0b4_28db) as u128);
+        (t >> 64) as u64 ^ t as u64
+    }
+
+    /// Fills `bytes` 8 bytes at a time.
+    #[inline(always)]
+    fn fill(&mut self, bytes: &mut [u8; LANES]) {
+        for chunk in bytes.chunks_exact_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+    }
+}
+
+/// Returns whether `step()` handles `params`, the rest is stepped by the kernel.
+pub(crate) fn supports(params: &Params) -> bool {
+    params.lit
+        && matches!(
+            params.distribution,
+            Distribution::Classic | Distribution::Uniform
+        )
+        && params.spread == (1.0, 1.0)
+        && params.wind_map.is_empty()
+        && params.cooling.is_none()
+        && params.cooling_curve.is_none()
+}
+
+/// Moves the heat of a fire up a single step like `kernel::step()`, for the settings `supports()`,
+/// drawing from a generator seeded with `seed`. `rows` is scratch space for at least two rows.
+/// Returns the highest row given heat, `height` if none.
+pub(crate) fn step<H: Heat>(
+    heat: &mut [H],
+    width: usize,
+    height: usize,
+    params: &Params,
+    rows: &mut [u8],
+    seed: u64,
+) -> usize {
+    if width == 0 || height < 2 {
+        return height;
+    }
+    let mut rng = WyRand(seed);
+    let classic = params.distribution == Distribution::Classic;
+    // A gust blows when the byte of a pixel is below the threshold.
+    let gust_threshold = (params.wind.abs().min(1.0) * 256.0) as u16;
+    let gust = if params.wind > 0.0 { 0 } else { 2 };
+    let mut top = height;
+
+    // The heat the pixels of the row being stepped read, and of the row above it.
+    let (mut row, mut next) = rows[..2 * width].split_at_mut(width);
+    for (lane, pixel) in row.iter_mut().zip(&heat[(height - 1) * width..]) {
+        *lane = pixel.heat() as u8;
+    }
+    for y in (1..height).rev() {
+        let above = &mut heat[(y - 1) * width..y * width];
+        for (lane, pixel) in next.iter_mut().zip(above.iter()) {
+            *lane = pixel.heat() as u8;
+        }
+        for start in (0..width).step_by(LANES) {
+            let lanes = LANES.min(width - start);
+            let mut src = [0u8; LANES];
+            src[..lanes].copy_from_slice(&row[start..start + lanes]);
+            let mut draws = [0u8; LANES];
+            let mut gusts = [0u8; LANES];
+            rng.fill(&mut draws);
+            rng.fill(&mut gusts);
+
+            // Branch free, so this loop is vectorized.
+            let mut dx = [0u8; LANES];
+            let mut dst = [0u8; LANES];
+            for i in 0..LANES {
+                let rand = if classic {
+                    // 0 to 5 from the byte, rounded to 0, 1, 1, 2, 2, 3 like `Distribution::Classic`.
+                    (((draws[i] as u16 * 6) >> 8) as u8 + 1) >> 1
+                } else {
+                    draws[i] & 3
+                };
+                let drift = if (gusts[i] as u16) < gust_threshold {
+                    gust
+                } else {
+                    rand
+                };
+                // Offset by 2, so moving up to 2 columns left or 1 right is 0 to 3.
+                dx[i] = 3 - drift;
+                dst[i] = src[i].saturating_sub(rand & 1);
+            }
+
+            for i in 0..lanes {
+                let x = start + i;
+                if src[i] == 0 {
+                    above[x] = H::from_heat(0);
+                } else {
+                    let dst_x = kernel::offset_x(params.wrap, width, x, dx[i] as isize - 2);
+                    if dst[i] > 0 {
+                        top = top.min(y - 1);
+                    }
+                    above[dst_x] = H::from_heat(dst[i] as usize);
+                    if dst_x == x + 1 {
+                        // Climbs again from the column to the right, see above.
+                        next[dst_x] = dst[i];
+                    }
+                }
+            }
+        }
+        core::mem::swap(&mut row, &mut next);
+    }
+    top
+}
+
+impl Doomfire {
+    /// Returns whether `update()` takes the vectorized path, see `set_simd()`.
+    pub fn simd(&self) -> bool {
+        self.simd
+    }
+
+    /// When `simd` is true `update()` steps the fire a row at a time with vector instructions and a faster generator,
+    /// about twice as fast for fullscreen fires. The flames look the same, but the fire no longer draws the same
+    /// random numbers as with `simd` off and the same seed. The default is false.
+    ///
+    /// Only the classic settings are vectorized: a lit fire with the `Classic` or `Uniform` distribution,
+    /// with wind and tilted up to 45° by gravity, but without `set_spread()`, `set_wind_map()`, `set_cooling()`
+    /// or `set_cooling_curve()`. Fires with other settings are updated as before.
+    /// # Examples
+    /// ```
+    /// # use doomfire::Doomfire;
+    /// let mut doomfire = Doomfire::new(1920, 1080);
+    /// doomfire.set_simd(true);
+    /// doomfire.ignite();
+    /// for _ in 0..10 {
+    ///     doomfire.update();
+    /// }
+    /// assert!(doomfire.heat()[1078 * 1920..1079 * 1920].iter().any(|&heat| heat > 0));
+    /// ```
+    pub fn set_simd(&mut self, simd: bool) {
+        self.simd = simd;
+    }
+
+    /// Moves the heat of every row up with `step()` if it handles the settings, returns whether it did.
+    pub(crate) fn update_simd(&mut self) -> bool {
+        if !self.simd || !supports(&self.params()) {
+            return false;
+        }
+        let top = self.with_buffers(|fire, heat, scratch, rng| {
+            let seed = rng.gen();
+            step(heat, fire.width, fire.height, &fire.params(), scratch, seed)
+        });
+        self.heated_from(top);
+        true
+    }
+}
@@ -0,0 +1,219 @@
+//! Fire palettes built from the PLAYPAL lump of a Doom WAD file.
+//!
+//! A PLAYPAL lump holds 14 palettes of 256 rgb colors (768 bytes each). The first one is the
+//! regular game palette, the others are the red (damage), yellow (pickup) and green (radiation suit) tints.
+//! Every color of the fire gradient is snapped to the closest color of the chosen palette so the fire
+//! only uses colors that exist in the game (or mod) the WAD belongs to.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{wad::Playpal, Doomfire};
+//!
+//! let playpal = Playpal::from_wad_file("doom.wad").unwrap();
+//! let mut doomfire = Doomfire::new(600, 400);
+//! doomfire.set_palette(playpal.fire_palette(0));
+//! ```
+use crate::PALETTE;
+use std::{error, fmt, fs, io, path::Path};
+
+/// The size in bytes of a single 256 color rgb palette.
+pub const PALETTE_SIZE: usize = 256 * 3;
+
+/// Errors returned while reading a WAD or PLAYPAL lump.
+#[derive(Debug)]
+pub enum WadError {
+    /// The WAD file could not be read.
+    Io(io::Error),
+    /// The data doesn't start with an `IWAD` or `PWAD` header.
+    NotAWad,
+    /// The header or lump directory points outside of the data.
+    Truncated,
+    /// The WAD doesn't contain a PLAYPAL lump.
+    MissingPlaypal,
+    /// The palette data isn't a (non zero) multiple of 768 bytes.
+    InvalidLength(usize),
+}
+
+impl fmt::Display for WadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WadError::Io(e) => write!(f, "could not read wad: {}", e),
+            WadError::NotAWad => write!(f, "missing IWAD or PWAD header"),
+            WadError::Truncated => write!(f, "wad is truncated"),
+            WadError::MissingPlaypal => write!(f, "wad contains no PLAYPAL lump"),
+            WadError::InvalidLength(len) => write!(
+                f,
+                "palette data is {} bytes, expected a multiple of {}",
+                len, PALETTE_SIZE
+            ),
+        }
+    }
+}
+
+impl error::Error for WadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            WadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WadError {
+    fn from(e: io::Error) -> Self {
+        WadError::Io(e)
+    }
+}
+
+/// The palettes of a PLAYPAL lump.
+#[derive(Debug, Clone)]
+pub struct Playpal {
+    data: Vec<u8>,
+}
+
+impl Playpal {
+    /// Reads the PLAYPAL lump from the WAD file at `path`.
+    pub fn from_wad_file<P: AsRef<Path>>(path: P) -> Result<Playpal, WadError> {
+        Playpal::from_wad(&fs::read(path)?)
+    }
+
+    /// Reads the PLAYPAL lump from the contents of a WAD file.
+    /// When a PWAD contains several PLAYPAL lumps the last one is used, like the game does.
+    /// # Examples
+    /// ```
+    /// # use doomfire::wad::{Playpal, PALETTE_SIZE};
+    /// // A WAD with a single all black PLAYPAL lump.
+    /// let mut wad = Vec::new();
+    /// wad.extend_from_slice(b"PWAD");
+    /// wad.extend_from_slice(&1i32.to_le_bytes()); // Number of lumps.
+    /// wad.extend_from_slice(&(12 + PALETTE_SIZE as i32).to_le_bytes()); // Directory offset.
+    /// wad.extend_from_slice(&[0; PALETTE_SIZE]);
+    /// wad.extend_from_slice(&12i32.to_le_bytes()); // Lump offset.
+    /// wad.extend_from_slice(&(PALETTE_SIZE as i32).to_le_bytes()); // Lump size.
+    /// wad.extend_from_slice(b"PLAYPAL\0");
+    ///
+    /// let playpal = Playpal::from_wad(&wad).unwrap();
+    /// assert_eq!(playpal.len(), 1);
+    /// ```
+    pub fn from_wad(wad: &[u8]) -> Result<Playpal, WadError> {
+        let magic = wad.get(0..4).ok_or(WadError::Truncated)?;
+        if magic != b"IWAD" && magic != b"PWAD" {
+            return Err(WadError::NotAWad);
+        }
+        let lump_count = read_i32(wad, 4)?;
+        let directory = read_i32(wad, 8)?;
+
+        for i in (0..lump_count).rev() {
+            // The offsets come from the file, so they may point past the end of the address space on 32 bit targets.
+            let entry = i
+                .checked_mul(16)
+                .and_then(|entry| entry.checked_add(directory))
+                .ok_or(WadError::Truncated)?;
+            let name = wad
+                .get(entry..)
+                .and_then(|entry| entry.get(8..16))
+                .ok_or(WadError::Truncated)?;
+            if name.starts_with(b"PLAYPAL") && name[7] == 0 {
+                let offset = read_i32(wad, entry)?;
+                let size = read_i32(wad, entry + 4)?;
+                let end = offset.checked_add(size).ok_or(WadError::Truncated)?;
+                let lump = wad.get(offset..end).ok_or(WadError::Truncated)?;
+                return Playpal::from_raw(lump);
+            }
+        }
+
+        Err(WadError::MissingPlaypal)
+    }
+
+    /// Uses raw palette data, e.g. an extracted PLAYPAL lump or a single 768 byte palette.
+    /// # Examples
+    /// ```
+    /// # use doomfire::wad::{Playpal, PALETTE_SIZE};
+    /// let playpal = Playpal::from_raw(&[0; PALETTE_SIZE]).unwrap();
+    /// assert_eq!(playpal.len(), 1);
+    /// assert!(Playpal::from_raw(&[0; 100]).is_err());
+    /// ```
+    pub fn from_raw(data: &[u8]) -> Result<Playpal, WadError> {
+        if data.is_empty() || data.len() % PALETTE_SIZE != 0 {
+            return Err(WadError::InvalidLength(data.len()));
+        }
+
+        Ok(Playpal {
+            data: data.to_vec(),
+        })
+    }
+
+    /// Returns the number of palettes, 14 for an unmodified Doom WAD.
+    pub fn len(&self) -> usize {
+        self.data.len() / PALETTE_SIZE
+    }
+
+    /// Always returns false, a `Playpal` contains at least one palette.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns color `index` of palette `palette` as an opaque rgba value.
+    /// # Panics
+    /// Panics if `palette` is not smaller than `len()`.
+    pub fn color(&self, palette: usize, index: u8) -> [u8; 4] {
+        let i = palette * PALETTE_SIZE + index as usize * 3;
+        let rgb = &self.data[i..i + 3];
+        [rgb[0], rgb[1], rgb[2], 0xFF]
+    }
+
+    /// Builds a fire palette by snapping every color of `PALETTE` to the closest color of palette `palette`.
+    /// # Panics
+    /// Panics if `palette` is not smaller than `len()`.
+    /// # Examples
+    /// ```
+    /// # use doomfire::wad::{Playpal, PALETTE_SIZE};
+    /// let playpal = Playpal::from_raw(&[0; PALETTE_SIZE]).unwrap();
+    /// // A palette with only black colors gives an all black fire.
+    /// assert!(playpal.fire_palette(0).iter().all(|c| *c == [0, 0, 0, 0xFF]));
+    /// ```
+    pub fn fire_palette(&self, palette: usize) -> [[u8; 4]; 37] {
+        let mut fire = [[0; 4]; 37];
+        for (dst, src) in fire.iter_mut().zip(PALETTE.iter()) {
+            let closest = (0..=255)
+                .min_by_key(|&i| distance(&self.color(palette, i), src))
+                .unwrap();
+            *dst = self.color(palette, closest);
+        }
+        fire
+    }
+
+    /// Builds a fire palette from explicit color indices of palette `palette`, coldest first.
+    /// Useful for mods that remap their colors so the closest match no longer looks like fire.
+    /// # Panics
+    /// Panics if `palette` is not smaller than `len()`.
+    pub fn fire_palette_from_indices(&self, palette: usize, indices: &[u8; 37]) -> [[u8; 4]; 37] {
+        let mut fire = [[0; 4]; 37];
+        for (dst, &i) in fire.iter_mut().zip(indices.iter()) {
+            *dst = self.color(palette, i);
+        }
+        fire
+    }
+}
+
+/// Reads a (non negative) little endian i32 at `offset`.
+fn read_i32(data: &[u8], offset: usize) -> Result<usize, WadError> {
+    let bytes = data
+        .get(offset..)
+        .and_then(|data| data.get(..4))
+        .ok_or(WadError::Truncated)?;
+    let value = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if value < 0 {
+        return Err(WadError::Truncated);
+    }
+    Ok(value as usize)
+}
+
+/// Squared distance between the rgb channels of two colors.
+fn distance(a: &[u8; 4], b: &[u8; 4]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .take(3)
+        .map(|(&a, &b)| (a as i32 - b as i32).pow(2) as u32)
+        .sum()
+}
@@ -0,0 +1,60 @@
+//! Drawing into browser `ImageData` and canvases, so wasm applications don't have to copy frames across the JS boundary by hand.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{web::Canvas, Doomfire};
+//! use web_sys::CanvasRenderingContext2d;
+//!
+//! # fn context() -> CanvasRenderingContext2d { unimplemented!() }
+//! let context: CanvasRenderingContext2d = context();
+//! let mut doomfire = Doomfire::new(320, 168);
+//! let mut canvas = Canvas::new(&doomfire);
+//! doomfire.ignite();
+//!
+//! // In a requestAnimationFrame callback.
+//! doomfire.update();
+//! canvas.blit(&doomfire, &context).unwrap();
+//! ```
+use crate::Doomfire;
+use wasm_bindgen::{Clamped, JsValue};
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+/// Keeps a frame buffer around between frames to hand to the browser without allocating.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    frame: Clamped<Vec<u8>>,
+}
+
+impl Canvas {
+    /// Returns a canvas with room for a frame of `fire`.
+    pub fn new(fire: &Doomfire) -> Canvas {
+        Canvas {
+            frame: Clamped(vec![0; fire.width() * fire.height() * 4]),
+        }
+    }
+
+    /// Draws `fire` into the buffer (resizing it if the size of the fire changed) and returns it,
+    /// laid out as `ImageData` expects: rgba, row by row starting at the top left.
+    pub fn draw(&mut self, fire: &Doomfire) -> &mut Clamped<Vec<u8>> {
+        self.frame.0.resize(fire.width() * fire.height() * 4, 0);
+        fire.draw(&mut self.frame.0);
+        &mut self.frame
+    }
+
+    /// Draws `fire` and wraps the buffer in an `ImageData`.
+    pub fn image_data(&mut self, fire: &Doomfire) -> Result<ImageData, JsValue> {
+        let (width, height) = (fire.width() as u32, fire.height() as u32);
+        let frame = self.draw(fire);
+        ImageData::new_with_u8_clamped_array_and_sh(Clamped(&frame.0), width, height)
+    }
+
+    /// Draws `fire` onto the top left corner of a 2d canvas context.
+    /// Scale the canvas with css (`image-rendering: pixelated` keeps it sharp) instead of drawing a bigger fire.
+    pub fn blit(
+        &mut self,
+        fire: &Doomfire,
+        context: &CanvasRenderingContext2d,
+    ) -> Result<(), JsValue> {
+        context.put_image_data(&self.image_data(fire)?, 0.0, 0.0)
+    }
+}
@@ -0,0 +1,121 @@
+//! Uploading frames to a `wgpu::Texture`, to sample the fire in your own render pipeline.
+//!
+//! # Example
+//! ```no_run
+//! use doomfire::{wgpu_interop::FireTexture, Doomfire};
+//!
+//! # fn device() -> (wgpu::Device, wgpu::Queue) { unimplemented!() }
+//! let (device, queue) = device();
+//! let mut doomfire = Doomfire::new(320, 168);
+//! let mut texture = FireTexture::new(&device, &doomfire);
+//! doomfire.ignite();
+//!
+//! // Every frame, before recording the passes that sample `texture.view()`.
+//! doomfire.update();
+//! texture.upload(&device, &queue, &doomfire);
+//! ```
+use crate::Doomfire;
+use wgpu::{
+    Device, Extent3d, Origin3d, Queue, TexelCopyBufferLayout, TexelCopyTextureInfo, Texture,
+    TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
+    COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+/// A texture the size of a fire, along with the buffers to fill it without allocating every frame.
+#[derive(Debug)]
+pub struct FireTexture {
+    texture: Texture,
+    view: TextureView,
+    frame: Vec<u8>,
+    padded: Vec<u8>,
+}
+
+/// Rounds the length of a row of rgba pixels up to what wgpu copies in one go.
+fn padded_bytes_per_row(width: usize) -> usize {
+    let alignment = COPY_BYTES_PER_ROW_ALIGNMENT as usize;
+    (width * 4).div_ceil(alignment) * alignment
+}
+
+impl FireTexture {
+    /// The format of the texture, the palettes are srgb colors.
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+    /// Returns a texture with the size of `fire` that can be sampled and copied to.
+    pub fn new(device: &Device, fire: &Doomfire) -> FireTexture {
+        let texture = create_texture(device, fire.width(), fire.height());
+        FireTexture {
+            view: texture.create_view(&Default::default()),
+            texture,
+            frame: Vec::new(),
+            padded: Vec::new(),
+        }
+    }
+
+    /// Draws `fire` and copies it to the texture, recreating the texture if the size of the fire changed.
+    /// Fetch `view()` again after resizing a fire, the old view still points to the old texture.
+    pub fn upload(&mut self, device: &Device, queue: &Queue, fire: &Doomfire) {
+        let (width, height) = (fire.width(), fire.height());
+        let size = self.texture.size();
+        if size.width as usize != width || size.height as usize != height {
+            self.texture = create_texture(device, width, height);
+            self.view = self.texture.create_view(&Default::default());
+        }
+
+        self.frame.resize(width * height * 4, 0);
+        fire.draw(&mut self.frame);
+
+        // Rows are padded so wgpu can copy the frame into its staging buffer in one go.
+        let bytes_per_row = padded_bytes_per_row(width);
+        self.padded.resize(bytes_per_row * height, 0);
+        for (padded, row) in self
+            .padded
+            .chunks_exact_mut(bytes_per_row)
+            .zip(self.frame.chunks_exact(width * 4))
+        {
+            padded[..row.len()].copy_from_slice(row);
+        }
+
+        queue.write_texture(
+            TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            &self.padded,
+            TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row as u32),
+                rows_per_image: Some(height as u32),
+            },
+            self.texture.size(),
+        );
+    }
+
+    /// Returns the texture.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Returns a view of the whole texture, to put in a bind group.
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+}
+
+fn create_texture(device: &Device, width: usize, height: usize) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some("doomfire"),
+        size: Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: FireTexture::FORMAT,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}